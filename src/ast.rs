@@ -1,5 +1,6 @@
 //! `ast` contains the Abstract Syntax Tree (`AST`) representation.
 
+use crate::integer::Integer;
 use crate::{Identifier, Position};
 
 /// An Abstract Syntax Tree with the position in the file.
@@ -23,19 +24,131 @@ pub enum ASTType {
         arguments: Option<Vec<Identifier>>,
         value: Box<AST>,
     },
+    /// A `defmacro` expression, in the form `(defmacro (m x) (foo x))`, to define the macro `m`.
+    /// A call to `m` is replaced by the value of `(foo x)`, with `x` bound to the code of the
+    /// argument, before the call is evaluated.
+    Defmacro {
+        name: Identifier,
+        arguments: Vec<Identifier>,
+        value: Box<AST>,
+    },
+    /// An `import` expression, in the form `(import "lib.tini")` or `(import lib)`, which
+    /// evaluates the file `lib.tini` and defines `lib/f` for every definition `f` in it. In the
+    /// form `(import lib (only f g))`, only `f` and `g` are defined, without a prefix. `path` is
+    /// the path of the file, and `names` the names after `only`.
+    Import {
+        path: String,
+        names: Option<Vec<Identifier>>,
+    },
+    /// A `set!` expression, in the form `(set! x foo)`, to assign the value of `foo` to the
+    /// existing variable `x`.
+    Set { name: Identifier, value: Box<AST> },
+    /// A `lambda` expression, in the form `(lambda (x y) (foo x y))`, which creates an anonymous
+    /// function.
+    Lambda {
+        arguments: Vec<Identifier>,
+        value: Box<AST>,
+    },
+    /// A `let` expression, in the form `(let ((x 1) (y 2)) (foo x y))`, which binds variables
+    /// in a new scope. Every value is evaluated before any variable is bound, so the values
+    /// cannot refer to the variables.
+    Let {
+        bindings: Vec<(Identifier, AST)>,
+        value: Box<AST>,
+    },
+    /// A `let*` expression, in the form `(let* ((x 1) (y x)) (foo x y))`, which binds variables
+    /// one after another, so a value can refer to the variables bound before it.
+    LetStar {
+        bindings: Vec<(Identifier, AST)>,
+        value: Box<AST>,
+    },
+    /// A `letrec` expression, in the form `(letrec ((f (lambda () (g))) (g ...)) (f))`, which
+    /// binds variables in a scope that the values are evaluated in, so functions can refer to
+    /// each other.
+    Letrec {
+        bindings: Vec<(Identifier, AST)>,
+        value: Box<AST>,
+    },
     /// An `if` expression, in the form `(if condition consequence alternative)`.
     If {
         condition: Box<AST>,
         consequence: Box<AST>,
         alternative: Box<AST>,
     },
-    /// A function call, in the form `(function param1 param2 ...)`.
-    FunctionCall {
+    /// A `cond` expression, in the form `(cond (c1 x1) (c2 x2) ... (else y))`, which evaluates
+    /// the expression of the first clause whose condition is true. The `else` clause is
+    /// optional.
+    Cond {
+        clauses: Vec<(AST, AST)>,
+        alternative: Option<Box<AST>>,
+    },
+    /// A `case` expression, in the form `(case key ((1 2) x) ((3) y) ... (else z))`, which
+    /// evaluates the expression of the first clause that contains the value of `key`. The
+    /// `else` clause is optional.
+    Case {
+        key: Box<AST>,
+        clauses: Vec<(Vec<Integer>, AST)>,
+        alternative: Option<Box<AST>>,
+    },
+    /// A `begin` expression, in the form `(begin x y ...)`, which evaluates its expressions in
+    /// order and returns the value of the last one. A parsed `begin` expression has at least one
+    /// expression; without expressions, it evaluates to `nil`.
+    Begin(Vec<AST>),
+    /// An `and` expression, in the form `(and x y ...)`, which evaluates its operands until one
+    /// is false.
+    And(Vec<AST>),
+    /// An `or` expression, in the form `(or x y ...)`, which evaluates its operands until one is
+    /// true.
+    Or(Vec<AST>),
+    /// A `not` expression, in the form `(not x)`.
+    Not(Box<AST>),
+    /// A `try` expression, in the form `(try x (catch e y))`, which evaluates `x`, and if that
+    /// results in an error, evaluates `y` with `e` bound to a description of the error.
+    Try {
+        value: Box<AST>,
         name: Identifier,
+        handler: Box<AST>,
+    },
+    /// A `quote` expression, in the form `(quote x)` or `'x`, which evaluates to the code `x` as
+    /// data.
+    Quote(Datum),
+    /// A `quasiquote` expression, in the form `(quasiquote x)` or `` `x ``, which evaluates to the
+    /// code `x` as data, except for the parts of `x` that are unquoted, in the form `(unquote y)`
+    /// or `,y`: these are evaluated, and their values are put in the data.
+    Quasiquote(Datum),
+    /// A function call, in the form `(function param1 param2 ...)`, where `function` can be any
+    /// expression that evaluates to a function.
+    FunctionCall {
+        function: Box<AST>,
         arguments: Vec<AST>,
     },
     /// An identifier.
     Identifier(Identifier),
     /// An integer.
-    Integer(i64),
+    Integer(Integer),
+    /// A float.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// A boolean.
+    Bool(bool),
+}
+
+/// Code that is data, as it is written in a `quote` or a `quasiquote` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Datum {
+    /// An identifier or a keyword.
+    Symbol(Identifier),
+    /// An integer.
+    Integer(Integer),
+    /// A float.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// A boolean.
+    Bool(bool),
+    /// A list, in the form `(x y ...)`.
+    List(Vec<Datum>),
+    /// An unquoted expression in a `quasiquote` expression, which is evaluated.
+    Unquote(Box<AST>),
 }
@@ -1,9 +1,24 @@
 use std::io::{self, BufRead, Write};
+use std::process;
+use std::thread;
+use tini::interpreter::{STACK_SIZE, STACK_SIZE_MAX_DEPTH};
 use tini::prelude::*;
 
 fn main() {
+    // Deeply recursive tini code needs a larger stack than the main thread has.
+    let repl = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("Error while spawning interpreter thread.");
+    if repl.join().is_err() {
+        process::exit(1);
+    }
+}
+
+fn run() {
     let stdin = io::stdin();
     let mut interpreter = Interpreter::new();
+    interpreter.set_max_depth(STACK_SIZE_MAX_DEPTH);
 
     loop {
         print!(">> ");
@@ -1,20 +1,38 @@
 use std::env;
 use std::fs;
+use std::path::Path;
 use std::process;
+use std::thread;
+use tini::interpreter::{STACK_SIZE, STACK_SIZE_MAX_DEPTH};
 use tini::prelude::*;
 
 fn main() {
-    let mut args = env::args().skip(1);
+    // Deeply recursive tini code needs a larger stack than the main thread has.
+    let interpreter = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("Error while spawning interpreter thread.");
+    if interpreter.join().is_err() {
+        process::exit(1);
+    }
+}
+
+fn run() {
+    let mut args = env::args().skip(1).peekable();
+    let strict = args.peek().map(|arg| arg == "--strict") == Some(true);
+    if strict {
+        args.next();
+    }
     let filename = match args.next() {
         Some(arg) => arg,
         None => {
             eprintln!("Error: too few arguments. Expected one.");
-            println!("Usage: tinii <file>");
+            println!("Usage: tinii [--strict] <file>");
             process::exit(1);
         }
     };
 
-    let input = match fs::read_to_string(filename) {
+    let input = match fs::read_to_string(&filename) {
         Ok(i) => i,
         Err(e) => {
             eprintln!("error while reading file {}.", e);
@@ -25,6 +43,14 @@ fn main() {
     let lexer = Lexer::new(&input);
     let parser = Parser::new(lexer);
     let mut interpreter = Interpreter::new();
+    interpreter.set_max_depth(STACK_SIZE_MAX_DEPTH);
+    interpreter.set_strict(strict);
+    // Imported files are searched next to the file, and then in the current directory.
+    if let Some(directory) = Path::new(&filename).parent() {
+        let mut search_path = vec![directory.to_path_buf()];
+        search_path.extend_from_slice(interpreter.search_path());
+        interpreter.set_search_path(search_path);
+    }
 
     for expr in parser {
         let expr = match expr {
@@ -34,12 +60,9 @@ fn main() {
                 process::exit(1);
             }
         };
-        match interpreter.eval(expr) {
-            Err(e) => {
-                eprintln!("error while running interpreter: {}.", e);
-                process::exit(1)
-            }
-            _ => {}
+        if let Err(e) = interpreter.eval(expr) {
+            eprintln!("error while running interpreter: {}.", e);
+            process::exit(1)
         }
     }
 }
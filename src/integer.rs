@@ -0,0 +1,205 @@
+//! `integer` contains `Integer`, the type of integers in `tini`.
+//!
+//! By default, `Integer` is `i64`. When the `bigint` feature is enabled, `Integer` is `BigInt`,
+//! an arbitrary-precision integer, and arithmetic never overflows. The functions in this module
+//! work with both representations.
+
+#[cfg(feature = "bigint")]
+mod bigint;
+
+use std::convert::TryFrom;
+
+#[cfg(feature = "bigint")]
+pub use self::bigint::*;
+
+/// The type of an integer in `tini`.
+#[cfg(not(feature = "bigint"))]
+pub type Integer = i64;
+
+/// The type of an integer in `tini`.
+#[cfg(feature = "bigint")]
+pub type Integer = BigInt;
+
+/// Returns `true` if `v` is zero.
+#[cfg(not(feature = "bigint"))]
+pub fn is_zero(v: &Integer) -> bool {
+    *v == 0
+}
+
+/// Returns `true` if `v` is less than zero.
+#[cfg(not(feature = "bigint"))]
+pub fn is_negative(v: &Integer) -> bool {
+    *v < 0
+}
+
+/// Convert `v` to a `usize`, returning `None` if it is negative or too large.
+#[cfg(not(feature = "bigint"))]
+pub fn to_usize(v: &Integer) -> Option<usize> {
+    usize::try_from(*v).ok()
+}
+
+/// Convert `v` to the nearest `f64`.
+#[cfg(not(feature = "bigint"))]
+pub fn to_f64(v: &Integer) -> f64 {
+    *v as f64
+}
+
+/// Parse an integer in base `radix`, optionally preceded by a sign, returning `None` if `s` is
+/// not a valid integer or if it does not fit in an `Integer`.
+#[cfg(not(feature = "bigint"))]
+pub fn from_str_radix(s: &str, radix: u32) -> Option<Integer> {
+    Integer::from_str_radix(s, radix).ok()
+}
+
+/// Add two integers, returning `None` if the result does not fit in an `Integer`.
+#[cfg(not(feature = "bigint"))]
+pub fn checked_add(l: &Integer, r: &Integer) -> Option<Integer> {
+    l.checked_add(*r)
+}
+
+/// Subtract `r` from `l`, returning `None` if the result does not fit in an `Integer`.
+#[cfg(not(feature = "bigint"))]
+pub fn checked_sub(l: &Integer, r: &Integer) -> Option<Integer> {
+    l.checked_sub(*r)
+}
+
+/// Multiply two integers, returning `None` if the result does not fit in an `Integer`.
+#[cfg(not(feature = "bigint"))]
+pub fn checked_mul(l: &Integer, r: &Integer) -> Option<Integer> {
+    l.checked_mul(*r)
+}
+
+/// Divide `l` by `r`, rounding towards zero, returning `None` if the result does not fit in an
+/// `Integer`. `r` must not be zero.
+#[cfg(not(feature = "bigint"))]
+pub fn checked_div(l: &Integer, r: &Integer) -> Option<Integer> {
+    l.checked_div(*r)
+}
+
+/// The remainder of dividing `l` by `r`, rounding towards zero, which has the sign of `l`.
+/// Returns `None` if the result does not fit in an `Integer`. `r` must not be zero.
+#[cfg(not(feature = "bigint"))]
+pub fn checked_rem(l: &Integer, r: &Integer) -> Option<Integer> {
+    l.checked_rem(*r)
+}
+
+/// Returns `true` if `v` is zero.
+#[cfg(feature = "bigint")]
+pub fn is_zero(v: &Integer) -> bool {
+    v.is_zero()
+}
+
+/// Returns `true` if `v` is less than zero.
+#[cfg(feature = "bigint")]
+pub fn is_negative(v: &Integer) -> bool {
+    v.is_negative()
+}
+
+/// Convert `v` to a `usize`, returning `None` if it is negative or too large.
+#[cfg(feature = "bigint")]
+pub fn to_usize(v: &Integer) -> Option<usize> {
+    v.to_i64().and_then(|v| usize::try_from(v).ok())
+}
+
+/// Convert `v` to the nearest `f64`.
+#[cfg(feature = "bigint")]
+pub fn to_f64(v: &Integer) -> f64 {
+    v.to_f64()
+}
+
+/// Parse an integer in base `radix`, optionally preceded by a sign, returning `None` if `s` is
+/// not a valid integer or if it does not fit in an `Integer`.
+#[cfg(feature = "bigint")]
+pub fn from_str_radix(s: &str, radix: u32) -> Option<Integer> {
+    Integer::from_str_radix(s, radix).ok()
+}
+
+/// Add two integers, returning `None` if the result does not fit in an `Integer`.
+#[cfg(feature = "bigint")]
+pub fn checked_add(l: &Integer, r: &Integer) -> Option<Integer> {
+    Some(l + r)
+}
+
+/// Subtract `r` from `l`, returning `None` if the result does not fit in an `Integer`.
+#[cfg(feature = "bigint")]
+pub fn checked_sub(l: &Integer, r: &Integer) -> Option<Integer> {
+    Some(l - r)
+}
+
+/// Multiply two integers, returning `None` if the result does not fit in an `Integer`.
+#[cfg(feature = "bigint")]
+pub fn checked_mul(l: &Integer, r: &Integer) -> Option<Integer> {
+    Some(l * r)
+}
+
+/// Divide `l` by `r`, rounding towards zero, returning `None` if the result does not fit in an
+/// `Integer`. `r` must not be zero.
+#[cfg(feature = "bigint")]
+pub fn checked_div(l: &Integer, r: &Integer) -> Option<Integer> {
+    Some(l / r)
+}
+
+/// The remainder of dividing `l` by `r`, rounding towards zero, which has the sign of `l`.
+/// Returns `None` if the result does not fit in an `Integer`. `r` must not be zero.
+#[cfg(feature = "bigint")]
+pub fn checked_rem(l: &Integer, r: &Integer) -> Option<Integer> {
+    Some(l % r)
+}
+
+/// The remainder of dividing `l` by `r`, rounding towards negative infinity, which has the sign
+/// of `r`. Returns `None` if the result does not fit in an `Integer`. `r` must not be zero.
+pub fn checked_mod(l: &Integer, r: &Integer) -> Option<Integer> {
+    let remainder = checked_rem(l, r)?;
+    if !is_zero(&remainder) && is_negative(&remainder) != is_negative(r) {
+        checked_add(&remainder, r)
+    } else {
+        Some(remainder)
+    }
+}
+
+/// The absolute value of `v`, returning `None` if the result does not fit in an `Integer`.
+pub fn checked_abs(v: &Integer) -> Option<Integer> {
+    if is_negative(v) {
+        checked_sub(&Integer::from(0), v)
+    } else {
+        Some(v.to_owned())
+    }
+}
+
+/// Raise `base` to the power `exponent`, returning `None` if the result does not fit in an
+/// `Integer`. `exponent` must not be negative.
+pub fn checked_pow(base: &Integer, exponent: &Integer) -> Option<Integer> {
+    let two = Integer::from(2);
+    let mut result = Integer::from(1);
+    let mut base = base.to_owned();
+    let mut exponent = exponent.to_owned();
+
+    // Exponentiation by squaring.
+    while !is_zero(&exponent) {
+        if !is_zero(&checked_rem(&exponent, &two)?) {
+            result = checked_mul(&result, &base)?;
+        }
+        exponent = checked_div(&exponent, &two)?;
+        if !is_zero(&exponent) {
+            base = checked_mul(&base, &base)?;
+        }
+    }
+
+    Some(result)
+}
+
+/// The greatest common divisor of `l` and `r`, which is never negative. Returns `None` if the
+/// result does not fit in an `Integer`.
+pub fn checked_gcd(l: &Integer, r: &Integer) -> Option<Integer> {
+    let mut l = checked_abs(l)?;
+    let mut r = checked_abs(r)?;
+
+    // Euclid's algorithm.
+    while !is_zero(&r) {
+        let remainder = checked_rem(&l, &r)?;
+        l = r;
+        r = remainder;
+    }
+
+    Some(l)
+}
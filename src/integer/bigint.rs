@@ -0,0 +1,406 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::str::FromStr;
+
+/// The base of a digit of the magnitude of a `BigInt`.
+const BASE: u64 = 1 << 32;
+
+/// The largest power of ten that fits in a digit, used to convert a `BigInt` to a string.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+/// The number of decimal digits in `DECIMAL_BASE`.
+const DECIMAL_DIGITS: usize = 9;
+
+/// An arbitrary-precision integer.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// Whether the integer is negative. Zero is never negative.
+    negative: bool,
+    /// The digits of the absolute value of the integer in base 2^32, with the least significant
+    /// digit first. The most significant digit is never zero, so zero has no digits.
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    /// Create a new `BigInt` with the value zero.
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    /// Returns `true` if the integer is zero.
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    /// Returns `true` if the integer is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Convert the integer to an `i64`, returning `None` if it does not fit.
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = match self.magnitude.len() {
+            0 => 0,
+            1 => u64::from(self.magnitude[0]),
+            2 => u64::from(self.magnitude[0]) | (u64::from(self.magnitude[1]) << 32),
+            _ => return None,
+        };
+        if self.negative {
+            // The magnitude of `i64::MIN` does not fit in an `i64` itself.
+            if magnitude == i64::MIN.unsigned_abs() {
+                Some(i64::MIN)
+            } else {
+                i64::try_from(magnitude).ok().map(|m| -m)
+            }
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// Convert the integer to the nearest `f64`, which is infinite if the integer is too large.
+    pub fn to_f64(&self) -> f64 {
+        // Parsing the decimal representation rounds correctly.
+        self.to_string().parse().unwrap()
+    }
+
+    /// Parse an integer in base `radix`, optionally preceded by a sign, like
+    /// `i64::from_str_radix`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range from 2 to 36.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix must be in the range from 2 to 36"
+        );
+
+        let (negative, digits) = if let Some(digits) = s.strip_prefix('-') {
+            (true, digits)
+        } else if let Some(digits) = s.strip_prefix('+') {
+            (false, digits)
+        } else {
+            (false, s)
+        };
+
+        if digits.is_empty() {
+            return Err(ParseBigIntError);
+        }
+
+        let mut magnitude = Vec::new();
+        for ch in digits.chars() {
+            let digit = ch.to_digit(radix).ok_or(ParseBigIntError)?;
+            mul_add_small(&mut magnitude, radix, digit);
+        }
+
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+
+    /// Create a `BigInt` from a sign and a magnitude, removing leading zero digits.
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> BigInt {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        BigInt {
+            negative,
+            magnitude,
+        }
+    }
+}
+
+/// Compare two magnitudes.
+fn cmp_magnitude(l: &[u32], r: &[u32]) -> Ordering {
+    l.len()
+        .cmp(&r.len())
+        .then_with(|| l.iter().rev().cmp(r.iter().rev()))
+}
+
+/// Add two magnitudes.
+fn add_magnitude(l: &[u32], r: &[u32]) -> Vec<u32> {
+    let (long, short) = if l.len() >= r.len() { (l, r) } else { (r, l) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+    for (i, digit) in long.iter().enumerate() {
+        let total = u64::from(*digit) + u64::from(*short.get(i).unwrap_or(&0)) + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    if carry != 0 {
+        sum.push(carry as u32);
+    }
+    sum
+}
+
+/// Subtract the magnitude `r` from the magnitude `l`, which must not be smaller than `r`.
+fn sub_magnitude(l: &[u32], r: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(l.len());
+    let mut borrow = 0;
+    for (i, digit) in l.iter().enumerate() {
+        let subtrahend = u64::from(*r.get(i).unwrap_or(&0)) + borrow;
+        let digit = u64::from(*digit);
+        if digit >= subtrahend {
+            difference.push((digit - subtrahend) as u32);
+            borrow = 0;
+        } else {
+            difference.push((digit + BASE - subtrahend) as u32);
+            borrow = 1;
+        }
+    }
+    difference
+}
+
+/// Multiply two magnitudes.
+fn mul_magnitude(l: &[u32], r: &[u32]) -> Vec<u32> {
+    let mut product = vec![0; l.len() + r.len()];
+    for (i, l) in l.iter().enumerate() {
+        let mut carry = 0;
+        for (j, r) in r.iter().enumerate() {
+            let total = u64::from(*l) * u64::from(*r) + u64::from(product[i + j]) + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + r.len()] = carry as u32;
+    }
+    product
+}
+
+/// Divide the magnitude `l` by the non-empty magnitude `r`, returning the quotient and the
+/// remainder.
+fn div_rem_magnitude(l: &[u32], r: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if r.len() == 1 {
+        let mut quotient = l.to_vec();
+        let remainder = div_rem_small(&mut quotient, r[0]);
+        return (quotient, vec![remainder]);
+    }
+
+    // Long division, one bit at a time.
+    let mut quotient = vec![0; l.len()];
+    let mut remainder = Vec::with_capacity(r.len() + 1);
+    for i in (0..l.len() * 32).rev() {
+        // Shift the next bit of `l` into the remainder.
+        let mut carry = (l[i / 32] >> (i % 32)) & 1;
+        for digit in remainder.iter_mut() {
+            let shifted = (*digit << 1) | carry;
+            carry = *digit >> 31;
+            *digit = shifted;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+
+        if cmp_magnitude(&remainder, r) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, r);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+/// Multiply a magnitude by a single digit and add another single digit to it, in place.
+fn mul_add_small(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = u64::from(addend);
+    for digit in magnitude.iter_mut() {
+        let total = u64::from(*digit) * u64::from(factor) + carry;
+        *digit = total as u32;
+        carry = total >> 32;
+    }
+    if carry != 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+/// Divide a magnitude by a single, non-zero digit in place, returning the remainder.
+fn div_rem_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0;
+    for digit in magnitude.iter_mut().rev() {
+        let total = (remainder << 32) | u64::from(*digit);
+        *digit = (total / u64::from(divisor)) as u32;
+        remainder = total % u64::from(divisor);
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+impl From<i64> for BigInt {
+    fn from(v: i64) -> BigInt {
+        let magnitude = v.unsigned_abs();
+        BigInt::from_parts(v < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl From<i32> for BigInt {
+    fn from(v: i32) -> BigInt {
+        BigInt::from(i64::from(v))
+    }
+}
+
+impl PartialEq<i64> for BigInt {
+    fn eq(&self, other: &i64) -> bool {
+        let other = BigInt::from(*other);
+        *self == other
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(
+                self.negative,
+                add_magnitude(&self.magnitude, &other.magnitude),
+            );
+        }
+
+        match cmp_magnitude(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(
+                other.negative,
+                sub_magnitude(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::from_parts(
+                self.negative,
+                sub_magnitude(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            mul_magnitude(&self.magnitude, &other.magnitude),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    /// Divide, rounding towards zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    fn div(self, other: &BigInt) -> BigInt {
+        assert!(!other.is_zero(), "attempt to divide by zero");
+        let (quotient, _) = div_rem_magnitude(&self.magnitude, &other.magnitude);
+        BigInt::from_parts(self.negative != other.negative, quotient)
+    }
+}
+
+impl Rem for &BigInt {
+    type Output = BigInt;
+
+    /// The remainder of dividing, rounding towards zero, which has the sign of `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    fn rem(self, other: &BigInt) -> BigInt {
+        assert!(
+            !other.is_zero(),
+            "attempt to calculate the remainder with a divisor of zero"
+        );
+        let (_, remainder) = div_rem_magnitude(&self.magnitude, &other.magnitude);
+        BigInt::from_parts(self.negative, remainder)
+    }
+}
+
+/// The error returned when a string cannot be parsed into a `BigInt`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl error::Error for ParseBigIntError {}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parse a decimal integer, optionally preceded by a sign, like `i64::from_str`.
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        BigInt::from_str_radix(s, 10)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Collect the digits in base `DECIMAL_BASE`, least significant first.
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_empty() {
+            chunks.push(div_rem_small(&mut magnitude, DECIMAL_BASE));
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(chunk) = chunks.next() {
+            write!(f, "{}", chunk)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:0width$}", chunk, width = DECIMAL_DIGITS)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -3,126 +3,668 @@
 pub mod builtins;
 mod environment;
 mod error;
+mod expander;
+mod modules;
+mod quote;
 mod value;
 
 pub use self::environment::*;
 pub use self::error::*;
+pub use self::quote::*;
 pub use self::value::*;
 
-use crate::ast::{ASTType, AST};
+use self::modules::Module;
+
+use crate::ast::{ASTType, Datum, AST};
+use crate::integer::Integer;
 use crate::{Identifier, Position};
+use std::collections::HashMap;
+use std::mem;
+use std::path::PathBuf;
 use std::rc::Rc;
 
+/// The default maximum depth of an `Interpreter`; see `Interpreter::set_max_depth`.
+///
+/// Every level of depth uses at most about 6 KiB of the Rust stack in a debug build, and less
+/// than 2 KiB in a release build. So evaluation at this depth fits in the stack of a main thread,
+/// which is 8 MiB on most platforms, also in a debug build; a thread with a smaller stack needs a
+/// lower maximum depth. See `STACK_SIZE` for deeper recursion.
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
+
+/// The stack size of a thread that can evaluate expressions up to `STACK_SIZE_MAX_DEPTH` deep.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// The maximum depth of an `Interpreter` that runs on a thread with a stack of `STACK_SIZE`.
+pub const STACK_SIZE_MAX_DEPTH: usize = 10_000;
+
 /// `Interpreter` executes expressions (`AST`s).
 pub struct Interpreter {
-    /// `env` contains the variable definitions.
+    /// `env` contains the top-level variable definitions.
     env: Environment,
+    /// `call_stack` contains the function calls that are being evaluated.
+    call_stack: Vec<Call>,
+    /// `builtin_env` is the environment in which the innermost call to a built-in function that
+    /// is being evaluated is evaluated.
+    builtin_env: Environment,
+    /// `depth` is the number of expressions that are being evaluated, each inside the previous
+    /// one; see `Interpreter::set_max_depth`.
+    depth: usize,
+    /// `max_depth` is the maximum of `depth` and of the length of `call_stack`.
+    max_depth: usize,
+    /// `strict` is whether only booleans can be used as conditions.
+    strict: bool,
+    /// `macros` contains the functions of the macros that are defined, by name.
+    macros: HashMap<Identifier, Rc<Value>>,
+    /// `symbols` is the number of symbols made by the built-in function `gensym`.
+    symbols: usize,
+    /// `search_path` contains the directories in which imported files are searched.
+    search_path: Vec<PathBuf>,
+    /// `modules` contains the files that were imported, by canonical path.
+    modules: HashMap<PathBuf, Module>,
+    /// `importing` contains the files that are being imported, from the outermost to the
+    /// innermost import.
+    importing: Vec<Module>,
 }
 
 impl Default for Interpreter {
     fn default() -> Interpreter {
-        let mut env = Environment::new();
-        builtins::add_builtins_to_environment(&mut env);
-        Interpreter { env }
+        let env = Environment::new();
+        builtins::add_builtins_to_environment(&env);
+        Interpreter {
+            builtin_env: env.clone(),
+            env,
+            call_stack: Vec::new(),
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            strict: false,
+            macros: HashMap::new(),
+            symbols: 0,
+            search_path: vec![PathBuf::from(".")],
+            modules: HashMap::new(),
+            importing: Vec::new(),
+        }
     }
 }
 
 impl Interpreter {
-    /// Create a new `Interpreter`.
+    /// Create a new `Interpreter`, with a maximum depth of `DEFAULT_MAX_DEPTH`.
     pub fn new() -> Interpreter {
         Interpreter::default()
     }
 
-    /// Evaluate an expression.
+    /// Get the maximum depth.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Set the maximum depth. The depth is the number of expressions that are being evaluated,
+    /// each inside the previous one, like the argument of a call inside the body of the function
+    /// that makes the call. An expression in tail position, like the body of a function, replaces
+    /// the expression it is in, so tail calls do not increase the depth. When an expression would
+    /// be evaluated deeper than the maximum depth, or the call stack would become longer than it,
+    /// `InterpreterError::StackOverflow` is returned.
+    ///
+    /// The thread that evaluates the expressions must have a stack that is large enough for this
+    /// depth, or it overflows first, which aborts the process; see `DEFAULT_MAX_DEPTH` and
+    /// `STACK_SIZE`.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Returns whether the interpreter is in strict mode.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Set whether the interpreter is in strict mode. In strict mode, only booleans can be used
+    /// as conditions, for example in `if` expressions; any other value results in
+    /// `InterpreterError::TypeError`. Otherwise, any value can be used as a condition; see
+    /// `Value::is_truthy`.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Get the directories in which imported files are searched.
+    pub fn search_path(&self) -> &[PathBuf] {
+        &self.search_path
+    }
+
+    /// Set the directories in which imported files are searched, in order. By default, only the
+    /// current directory is searched. A file that is imported by an imported file is searched in
+    /// the directory of the importing file first.
+    pub fn set_search_path(&mut self, search_path: Vec<PathBuf>) {
+        self.search_path = search_path;
+    }
+
+    /// Evaluate an expression in the top-level environment, after expanding its macros; see
+    /// `Interpreter::expand`.
     pub fn eval(&mut self, expression: AST) -> Result<Rc<Value>, InterpreterError> {
+        let expression = self.expand(expression)?;
+        let env = self.env.clone();
+        self.eval_in(expression, &env)
+    }
+
+    /// Evaluate an expression in the environment `env`.
+    ///
+    /// This is the only way the evaluation of an expression recurses, so `depth` is increased
+    /// here, and an expression that would be evaluated deeper than `max_depth` results in
+    /// `InterpreterError::StackOverflow`.
+    fn eval_in(
+        &mut self,
+        expression: AST,
+        env: &Environment,
+    ) -> Result<Rc<Value>, InterpreterError> {
+        if self.depth >= self.max_depth {
+            return Err(InterpreterError::StackOverflow {
+                depth: self.depth,
+                position: expression.position,
+                backtrace: self.call_stack.clone(),
+            });
+        }
+
+        self.depth += 1;
+        let calls = self.call_stack.len();
+        let result = self.eval_loop(expression, env, calls);
+        // Pop the call made by `eval_loop`, also when it returned an error.
+        self.call_stack.truncate(calls);
+        self.depth -= 1;
+        result
+    }
+
+    /// Evaluate an expression in the environment `env`, with `depth` the length of the call stack
+    /// when the evaluation started.
+    ///
+    /// Expressions in tail position – the branches of an `if` expression and the body of a
+    /// function – are evaluated by looping instead of recursing, so tail calls do not grow the
+    /// Rust stack.
+    ///
+    /// This function is on the Rust stack once for every expression that is being evaluated, so
+    /// most expressions are evaluated by methods of their own, to keep its stack frame small.
+    fn eval_loop(
+        &mut self,
+        mut expression: AST,
+        env: &Environment,
+        depth: usize,
+    ) -> Result<Rc<Value>, InterpreterError> {
         use self::ASTType::*;
 
-        let position = expression.position;
+        let mut env = env.clone();
 
-        match expression.ast {
-            Integer(v) => Ok(Rc::new(Value::Integer(v))),
-            // Variable definition.
-            Define {
-                name,
-                arguments: None,
+        loop {
+            let position = expression.position;
+
+            let tail = match expression.ast {
+                Integer(_) | Float(_) | String(_) | Bool(_) => Ok(Tail::literal(expression.ast)),
+                Quote(datum) | Quasiquote(datum) => self.eval_datum(datum, &env).map(Tail::Value),
+                Define {
+                    name,
+                    arguments,
+                    value,
+                } => self.eval_define(name, arguments, *value, &env),
+                // Macros are defined when they are expanded.
+                Defmacro { .. } => Ok(Tail::zero()),
+                Import { path, names } => self
+                    .import(&path, names.as_deref(), &env, position)
+                    .map(|()| Tail::zero()),
+                Set { name, value } => self.eval_set(name, *value, &env, position),
+                Lambda { arguments, value } => Ok(self.eval_lambda(arguments, *value, &env)),
+                Let { bindings, value } => self.eval_let(bindings, value, &mut env),
+                LetStar { bindings, value } => self.eval_let_star(bindings, value, &mut env),
+                Letrec { bindings, value } => self.eval_letrec(bindings, value, &mut env),
+                If {
+                    condition,
+                    consequence,
+                    alternative,
+                } => self.eval_if(*condition, consequence, alternative, &env),
+                Cond {
+                    clauses,
+                    alternative,
+                } => self.eval_cond(clauses, alternative, &env),
+                Case {
+                    key,
+                    clauses,
+                    alternative,
+                } => self.eval_case(*key, clauses, alternative, &env),
+                Try {
+                    value,
+                    name,
+                    handler,
+                } => self.eval_try(*value, name, handler, &mut env),
+                Begin(expressions) => self.eval_begin(expressions, &env),
+                And(operands) => self.eval_and_or(operands, true, &env),
+                Or(operands) => self.eval_and_or(operands, false, &env),
+                Not(operand) => self.eval_not(*operand, &env),
+                FunctionCall {
+                    function,
+                    arguments,
+                } => self.eval_call(*function, arguments, &mut env, position, depth),
+                Identifier(name) => env
+                    .get(&name)
+                    .map(Tail::Value)
+                    .ok_or(InterpreterError::UnknownVariable { name, position }),
+            };
+
+            expression = match tail? {
+                Tail::Value(value) => return Ok(value),
+                Tail::Expression(expression) => *expression,
+            };
+        }
+    }
+
+    /// Evaluate a `define` expression in `env`: a variable definition if `arguments` is `None`,
+    /// and a function definition otherwise.
+    fn eval_define(
+        &mut self,
+        name: Identifier,
+        arguments: Option<Vec<Identifier>>,
+        value: AST,
+        env: &Environment,
+    ) -> Result<Tail, InterpreterError> {
+        let value = match arguments {
+            None => self.eval_in(value, env)?,
+            Some(arguments) => Rc::new(Value::Function {
+                name: Some(name.clone()),
+                arguments,
                 value,
-            } => {
-                let value = self.eval(*value)?;
-                self.env.set(name, value);
-                Ok(Rc::new(Value::Integer(0)))
+                env: env.clone(),
+            }),
+        };
+        env.set(name, value);
+        Ok(Tail::zero())
+    }
+
+    /// Evaluate a `set!` expression at `position` in `env`, which assigns to the nearest enclosing
+    /// binding of `name`.
+    fn eval_set(
+        &mut self,
+        name: Identifier,
+        value: AST,
+        env: &Environment,
+        position: Position,
+    ) -> Result<Tail, InterpreterError> {
+        let value = self.eval_in(value, env)?;
+        match env.assign(&name, value) {
+            Some(_) => Ok(Tail::zero()),
+            None => Err(InterpreterError::UnknownVariable { name, position }),
+        }
+    }
+
+    /// Evaluate a `lambda` expression in `env`, which makes a function that captures `env`.
+    fn eval_lambda(&self, arguments: Vec<Identifier>, value: AST, env: &Environment) -> Tail {
+        Tail::Value(Rc::new(Value::Function {
+            name: None,
+            arguments,
+            value,
+            env: env.clone(),
+        }))
+    }
+
+    /// Evaluate the bindings of a `let` expression in `env`. Its body is in tail position, and
+    /// `env` becomes the new frame the body is evaluated in, so the bindings do not leak into it.
+    fn eval_let(
+        &mut self,
+        bindings: Vec<(Identifier, AST)>,
+        value: Box<AST>,
+        env: &mut Environment,
+    ) -> Result<Tail, InterpreterError> {
+        let frame = env.child();
+        for (name, value) in bindings {
+            let value = self.eval_in(value, env)?;
+            frame.set(name, value);
+        }
+        *env = frame;
+        Ok(Tail::Expression(value))
+    }
+
+    /// Evaluate the bindings of a `let*` expression in `env`, like `Interpreter::eval_let`.
+    fn eval_let_star(
+        &mut self,
+        bindings: Vec<(Identifier, AST)>,
+        value: Box<AST>,
+        env: &mut Environment,
+    ) -> Result<Tail, InterpreterError> {
+        // Every variable is bound in its own frame, so a function that captures a variable is not
+        // affected when a later binding shadows it.
+        for (name, value) in bindings {
+            let value = self.eval_in(value, env)?;
+            *env = env.child();
+            env.set(name, value);
+        }
+        // The body gets a frame of its own, also when there are no bindings.
+        *env = env.child();
+        Ok(Tail::Expression(value))
+    }
+
+    /// Evaluate the bindings of a `letrec` expression in `env`, like `Interpreter::eval_let`.
+    fn eval_letrec(
+        &mut self,
+        bindings: Vec<(Identifier, AST)>,
+        value: Box<AST>,
+        env: &mut Environment,
+    ) -> Result<Tail, InterpreterError> {
+        *env = env.child();
+        for (name, value) in bindings {
+            let value = self.eval_in(value, env)?;
+            env.set(name, value);
+        }
+        Ok(Tail::Expression(value))
+    }
+
+    /// Evaluate the condition of an `if` expression in `env`. The branch that is chosen is in
+    /// tail position.
+    fn eval_if(
+        &mut self,
+        condition: AST,
+        consequence: Box<AST>,
+        alternative: Box<AST>,
+        env: &Environment,
+    ) -> Result<Tail, InterpreterError> {
+        let condition_position = condition.position;
+        let condition = self.eval_in(condition, env)?;
+        if self.is_true(&condition, condition_position)? {
+            Ok(Tail::Expression(consequence))
+        } else {
+            Ok(Tail::Expression(alternative))
+        }
+    }
+
+    /// Evaluate the conditions of a `cond` expression in `env` until one is true. The expression
+    /// of the chosen clause is in tail position; if no clause is chosen, the result is `0`.
+    fn eval_cond(
+        &mut self,
+        clauses: Vec<(AST, AST)>,
+        alternative: Option<Box<AST>>,
+        env: &Environment,
+    ) -> Result<Tail, InterpreterError> {
+        for (condition, value) in clauses {
+            let condition_position = condition.position;
+            let condition = self.eval_in(condition, env)?;
+            if self.is_true(&condition, condition_position)? {
+                return Ok(Tail::Expression(Box::new(value)));
             }
-            // Function definition.
-            Define {
-                name,
-                arguments: Some(arguments),
+        }
+        Ok(Tail::or_zero(alternative))
+    }
+
+    /// Evaluate the key of a `case` expression in `env`, and choose the clause that contains it.
+    /// The expression of the chosen clause is in tail position; if no clause is chosen, the result
+    /// is `0`.
+    fn eval_case(
+        &mut self,
+        key: AST,
+        clauses: Vec<(Vec<Integer>, AST)>,
+        alternative: Option<Box<AST>>,
+        env: &Environment,
+    ) -> Result<Tail, InterpreterError> {
+        // A key that is not an integer does not match any clause.
+        let key = self.eval_in(key, env)?;
+        if let Value::Integer(key) = &*key {
+            if let Some((_, value)) = clauses.into_iter().find(|(values, _)| values.contains(key)) {
+                return Ok(Tail::Expression(Box::new(value)));
+            }
+        }
+        Ok(Tail::or_zero(alternative))
+    }
+
+    /// Evaluate the expression of a `try` expression in `env`. The handler is in tail position,
+    /// but the expression is not, because its errors must be caught; when it is chosen, `env`
+    /// becomes the environment of the handler.
+    fn eval_try(
+        &mut self,
+        value: AST,
+        name: Identifier,
+        handler: Box<AST>,
+        env: &mut Environment,
+    ) -> Result<Tail, InterpreterError> {
+        match self.eval_in(value, env) {
+            Ok(value) => Ok(Tail::Value(value)),
+            Err(error) => {
+                *env = env.child();
+                env.set(name, error.into_value());
+                Ok(Tail::Expression(handler))
+            }
+        }
+    }
+
+    /// Evaluate the expressions of a `begin` expression in `env`, except the last one, which is in
+    /// tail position. A `begin` expression without expressions, which cannot be parsed but can be
+    /// built, evaluates to `nil`.
+    fn eval_begin(
+        &mut self,
+        mut expressions: Vec<AST>,
+        env: &Environment,
+    ) -> Result<Tail, InterpreterError> {
+        let last = match expressions.pop() {
+            Some(last) => last,
+            None => return Ok(Tail::Value(Rc::new(Value::Nil))),
+        };
+        for expression in expressions {
+            self.eval_in(expression, env)?;
+        }
+        Ok(Tail::Expression(Box::new(last)))
+    }
+
+    /// Evaluate the operands of an `and` expression if `and` is true, or of an `or` expression
+    /// otherwise, in `env`. The operands are evaluated until one decides the result, which is
+    /// returned. The last operand is in tail position, so it is not tested.
+    fn eval_and_or(
+        &mut self,
+        mut operands: Vec<AST>,
+        and: bool,
+        env: &Environment,
+    ) -> Result<Tail, InterpreterError> {
+        let last = match operands.pop() {
+            Some(last) => last,
+            None => return Ok(Tail::Value(Rc::new(Value::Bool(and)))),
+        };
+        for operand in operands {
+            let operand_position = operand.position;
+            let value = self.eval_in(operand, env)?;
+            if self.is_true(&value, operand_position)? != and {
+                return Ok(Tail::Value(value));
+            }
+        }
+        Ok(Tail::Expression(Box::new(last)))
+    }
+
+    /// Evaluate a `not` expression in `env`.
+    fn eval_not(&mut self, operand: AST, env: &Environment) -> Result<Tail, InterpreterError> {
+        let operand_position = operand.position;
+        let value = self.eval_in(operand, env)?;
+        let value = !self.is_true(&value, operand_position)?;
+        Ok(Tail::Value(Rc::new(Value::Bool(value))))
+    }
+
+    /// Evaluate a function call at `position` in `env`, with `depth` as in `eval_loop`. The body
+    /// of a function that is not built in is in tail position; `env` becomes the environment it
+    /// is evaluated in.
+    fn eval_call(
+        &mut self,
+        function: AST,
+        arguments: Vec<AST>,
+        env: &mut Environment,
+        position: Position,
+        depth: usize,
+    ) -> Result<Tail, InterpreterError> {
+        let name = match &function.ast {
+            ASTType::Identifier(name) => Some(name.clone()),
+            _ => None,
+        };
+        let call = Call { name, position };
+        let function = self.eval_in(function, env)?;
+        match &*function {
+            Value::Function {
+                arguments: names,
                 value,
+                env: closure,
+                ..
             } => {
-                let function = Value::Function {
-                    arguments,
-                    value: *value,
-                };
-                self.env.set(name, Rc::new(function));
-                Ok(Rc::new(Value::Integer(0)))
+                *env = self.bind_arguments(names, arguments, closure, env, position)?;
+                self.push_call(call, depth)?;
+                Ok(Tail::Expression(Box::new(value.clone())))
             }
-            If {
-                condition,
-                consequence,
-                alternative,
-            } => match &*self.eval(*condition)? {
-                Value::Integer(0) => self.eval(*alternative),
-                _ => self.eval(*consequence),
-            },
-            FunctionCall { name, arguments } => {
-                let function = match self.env.get(&name) {
-                    Some(f) => f,
-                    None => return Err(InterpreterError::UnknownVariable { name, position }),
-                };
-                match &*function {
-                    Value::Function {
-                        arguments: names,
-                        value,
-                    } => self.eval_function(
-                        names.clone(),
-                        arguments.clone(),
-                        value.clone(),
-                        position,
-                    ),
-                    Value::Builtin(function) => self.eval_builtin(&function, arguments),
-                    v => Err(InterpreterError::TypeError {
-                        expected: "function in function call",
-                        found: v.type_name(),
-                        position,
-                    }),
+            Value::Builtin(function) => self
+                .eval_builtin(*function, arguments, env, call, depth)
+                .map(Tail::Value),
+            v => Err(InterpreterError::TypeError {
+                expected: "function in function call",
+                found: v.type_name(),
+                position,
+            }),
+        }
+    }
+
+    /// Turn a datum into the value that represents it, evaluating its unquoted expressions in
+    /// `env`.
+    fn eval_datum(
+        &mut self,
+        datum: Datum,
+        env: &Environment,
+    ) -> Result<Rc<Value>, InterpreterError> {
+        Ok(Rc::new(match datum {
+            Datum::Symbol(name) => Value::Symbol(name),
+            Datum::Integer(v) => Value::Integer(v),
+            Datum::Float(v) => Value::Float(v),
+            Datum::String(s) => Value::String(s),
+            Datum::Bool(v) => Value::Bool(v),
+            Datum::List(elements) => {
+                let mut values = Vec::with_capacity(elements.len());
+                for element in elements {
+                    values.push(self.eval_datum(element, env)?);
                 }
+                return Ok(Value::list(values));
             }
-            Identifier(name) => match self.env.get(&name) {
-                Some(value) => Ok(value),
-                None => Err(InterpreterError::UnknownVariable { name, position }),
-            },
+            Datum::Unquote(expression) => return self.eval_in(*expression, env),
+        }))
+    }
+
+    /// Returns whether `value`, which is used as a condition at `position`, is true; see
+    /// `Value::is_truthy`. In strict mode, `value` must be a boolean.
+    fn is_true(&self, value: &Value, position: Position) -> Result<bool, InterpreterError> {
+        match value {
+            Value::Bool(_) => Ok(value.is_truthy()),
+            v if self.strict => Err(InterpreterError::TypeError {
+                expected: "bool as condition",
+                found: v.type_name(),
+                position,
+            }),
+            v => Ok(v.is_truthy()),
         }
     }
 
-    /// Evaluate a built-in function.
+    /// Get the position of the innermost call that is being evaluated.
+    fn current_position(&self) -> Position {
+        self.call_stack
+            .last()
+            .map(|call| call.position)
+            .unwrap_or_default()
+    }
+
+    /// Evaluate a built-in function, evaluating its arguments in `env`. The call is pushed onto
+    /// the call stack while the built-in function runs, because it may call other functions; see
+    /// `Interpreter::push_call` for `depth`.
     fn eval_builtin(
         &mut self,
-        builtin: &BuiltinFunction,
+        builtin: BuiltinFunction,
         arguments: Vec<AST>,
+        env: &Environment,
+        call: Call,
+        depth: usize,
     ) -> Result<Rc<Value>, InterpreterError> {
-        let arguments = arguments
-            .iter()
-            .map(|arg| self.eval(arg.clone()))
-            .collect::<Result<_, _>>()?;
-        builtin(arguments)
+        let mut values = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            values.push(self.eval_in(argument, env)?);
+        }
+        self.push_call(call, depth)?;
+        let caller = mem::replace(&mut self.builtin_env, env.clone());
+        let result = builtin(self, values);
+        self.builtin_env = caller;
+        result
+    }
+
+    /// Call `function` with `arguments`, which have already been evaluated. This is how built-in
+    /// functions call the functions that are passed to them.
+    ///
+    /// Errors are reported at the position of the innermost call that is being evaluated, which
+    /// is the call to the built-in function that called `apply`. The call to `function` is in the
+    /// backtrace with the name `function` was defined with, if any.
+    pub fn apply(
+        &mut self,
+        function: &Value,
+        arguments: Vec<Rc<Value>>,
+    ) -> Result<Rc<Value>, InterpreterError> {
+        let position = self.current_position();
+
+        match function {
+            Value::Function {
+                name,
+                arguments: names,
+                value,
+                env: closure,
+            } => {
+                if names.len() != arguments.len() {
+                    return Err(InterpreterError::ArgumentError {
+                        takes: names.len(),
+                        got: arguments.len(),
+                        position,
+                    });
+                }
+
+                let frame = closure.child();
+                for (name, arg) in names.iter().zip(arguments) {
+                    frame.set(name.clone(), arg);
+                }
+
+                let name = name.clone();
+                let depth = self.call_stack.len();
+                self.push_call(Call { name, position }, depth)?;
+                // The built-in function that called `apply` is on the Rust stack as well, so it
+                // counts as an expression that is being evaluated.
+                self.depth += 1;
+                let result = self.eval_in(value.clone(), &frame);
+                self.depth -= 1;
+                self.call_stack.truncate(depth);
+                result
+            }
+            Value::Builtin(function) => function(self, arguments),
+            v => Err(InterpreterError::TypeError {
+                expected: "function",
+                found: v.type_name(),
+                position,
+            }),
+        }
+    }
+
+    /// Push a call onto the call stack, with `depth` the length of the call stack when the
+    /// evaluation of the calling expression started. If that expression already made a call, this
+    /// call is a tail call and it replaces the previous call.
+    fn push_call(&mut self, call: Call, depth: usize) -> Result<(), InterpreterError> {
+        if self.call_stack.len() > depth {
+            self.call_stack.truncate(depth);
+        } else if self.call_stack.len() >= self.max_depth {
+            return Err(InterpreterError::StackOverflow {
+                depth: self.call_stack.len(),
+                position: call.position,
+                backtrace: self.call_stack.clone(),
+            });
+        }
+        self.call_stack.push(call);
+        Ok(())
     }
 
-    /// Evaluate a non-built-in function.
-    fn eval_function(
+    /// Bind the arguments of a call to a non-built-in function, returning the environment in
+    /// which the body of the function must be evaluated. The arguments are evaluated in `env`, the
+    /// environment of the caller, and bound in a new frame on top of `closure`, the environment
+    /// the function was defined in. Because the parameters are only bound in that new frame, an
+    /// error while evaluating the call leaves every other environment untouched.
+    fn bind_arguments(
         &mut self,
-        names: Vec<Identifier>,
+        names: &[Identifier],
         arguments: Vec<AST>,
-        body: AST,
+        closure: &Environment,
+        env: &Environment,
         position: Position,
-    ) -> Result<Rc<Value>, InterpreterError> {
+    ) -> Result<Environment, InterpreterError> {
         if names.len() != arguments.len() {
             return Err(InterpreterError::ArgumentError {
                 takes: names.len(),
@@ -131,28 +673,49 @@ impl Interpreter {
             });
         }
 
-        // Take values with the same name as the arguments out of the environment, to put
-        // them back in after the function is called.
-        let state: Vec<_> = names
-            .iter()
-            .map(|arg| (arg.clone(), self.env.get(&arg)))
-            .collect();
-
-        for (name, arg) in names.into_iter().zip(arguments) {
-            let arg = self.eval(arg)?;
-            self.env.set(name, arg);
+        let frame = closure.child();
+        for (name, arg) in names.iter().zip(arguments) {
+            let arg = self.eval_in(arg, env)?;
+            frame.set(name.clone(), arg);
         }
 
-        let return_value = self.eval(body)?;
+        Ok(frame)
+    }
+}
 
-        for (name, value) in state {
-            if let Some(value) = value {
-                self.env.set(name, value);
-            } else {
-                self.env.take(&name);
-            }
-        }
+/// What is left of an expression after the parts of it that are not in tail position are
+/// evaluated.
+enum Tail {
+    /// The value of the expression.
+    Value(Rc<Value>),
+    /// The expression in tail position, whose value is the value of the expression. It is boxed,
+    /// to keep the stack frames that contain a `Tail` small.
+    Expression(Box<AST>),
+}
+
+impl Tail {
+    /// The tail of a literal expression, which is its value.
+    fn literal(literal: ASTType) -> Tail {
+        Tail::Value(Rc::new(match literal {
+            ASTType::Integer(v) => Value::Integer(v),
+            ASTType::Float(v) => Value::Float(v),
+            ASTType::String(s) => Value::String(s),
+            ASTType::Bool(v) => Value::Bool(v),
+            _ => unreachable!("not a literal"),
+        }))
+    }
+
+    /// The tail of an expression whose value is `0`, like a definition.
+    fn zero() -> Tail {
+        Tail::Value(Rc::new(Value::Integer(0.into())))
+    }
 
-        Ok(return_value)
+    /// The tail of a `cond` or `case` expression whose clauses were not chosen: its `else`
+    /// clause, or `0` if it has none.
+    fn or_zero(alternative: Option<Box<AST>>) -> Tail {
+        match alternative {
+            Some(alternative) => Tail::Expression(alternative),
+            None => Tail::zero(),
+        }
     }
 }
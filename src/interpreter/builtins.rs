@@ -1,10 +1,13 @@
 //! `builtins` contains built-in function definitions.
 
-use crate::interpreter::{Environment, InterpreterError, Value};
+use crate::integer::{self, Integer};
+use crate::interpreter::{unquote, Environment, Interpreter, InterpreterError, Value};
+use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::rc::Rc;
 
 /// Add the built-in functions (defined in this module – `builtin`) to an `Environment`.
-pub fn add_builtins_to_environment(env: &mut Environment) {
+pub fn add_builtins_to_environment(env: &Environment) {
     env.set("+".to_string(), Rc::new(Value::Builtin(builtin_add)));
     env.set("-".to_string(), Rc::new(Value::Builtin(builtin_sub)));
     env.set("*".to_string(), Rc::new(Value::Builtin(builtin_mul)));
@@ -18,182 +21,757 @@ pub fn add_builtins_to_environment(env: &mut Environment) {
         Rc::new(Value::Builtin(builtin_is_less_than)),
     );
     env.set("print".to_string(), Rc::new(Value::Builtin(builtin_print)));
+    env.set("/".to_string(), Rc::new(Value::Builtin(builtin_div)));
+    env.set("mod".to_string(), Rc::new(Value::Builtin(builtin_mod)));
+    env.set("rem".to_string(), Rc::new(Value::Builtin(builtin_rem)));
+    env.set("abs".to_string(), Rc::new(Value::Builtin(builtin_abs)));
+    env.set("min".to_string(), Rc::new(Value::Builtin(builtin_min)));
+    env.set("max".to_string(), Rc::new(Value::Builtin(builtin_max)));
+    env.set("pow".to_string(), Rc::new(Value::Builtin(builtin_pow)));
+    env.set("gcd".to_string(), Rc::new(Value::Builtin(builtin_gcd)));
+    env.set(
+        "string-length".to_string(),
+        Rc::new(Value::Builtin(builtin_string_length)),
+    );
+    env.set(
+        "concat".to_string(),
+        Rc::new(Value::Builtin(builtin_concat)),
+    );
+    env.set(
+        "substring".to_string(),
+        Rc::new(Value::Builtin(builtin_substring)),
+    );
+    env.set(
+        "string->int".to_string(),
+        Rc::new(Value::Builtin(builtin_string_to_int)),
+    );
+    env.set(
+        "int->string".to_string(),
+        Rc::new(Value::Builtin(builtin_int_to_string)),
+    );
+    env.set("nil".to_string(), Rc::new(Value::Nil));
+    env.set("cons".to_string(), Rc::new(Value::Builtin(builtin_cons)));
+    env.set("car".to_string(), Rc::new(Value::Builtin(builtin_car)));
+    env.set("cdr".to_string(), Rc::new(Value::Builtin(builtin_cdr)));
+    env.set("list".to_string(), Rc::new(Value::Builtin(builtin_list)));
+    env.set(
+        "null?".to_string(),
+        Rc::new(Value::Builtin(builtin_is_null)),
+    );
+    env.set(
+        "length".to_string(),
+        Rc::new(Value::Builtin(builtin_length)),
+    );
+    env.set(
+        "append".to_string(),
+        Rc::new(Value::Builtin(builtin_append)),
+    );
+    env.set(
+        "reverse".to_string(),
+        Rc::new(Value::Builtin(builtin_reverse)),
+    );
+    env.set("map".to_string(), Rc::new(Value::Builtin(builtin_map)));
+    env.set(
+        "filter".to_string(),
+        Rc::new(Value::Builtin(builtin_filter)),
+    );
+    env.set("fold".to_string(), Rc::new(Value::Builtin(builtin_fold)));
+    env.set("box".to_string(), Rc::new(Value::Builtin(builtin_box)));
+    env.set("unbox".to_string(), Rc::new(Value::Builtin(builtin_unbox)));
+    env.set(
+        "set-box!".to_string(),
+        Rc::new(Value::Builtin(builtin_set_box)),
+    );
+    env.set(
+        "string->symbol".to_string(),
+        Rc::new(Value::Builtin(builtin_string_to_symbol)),
+    );
+    env.set(
+        "symbol->string".to_string(),
+        Rc::new(Value::Builtin(builtin_symbol_to_string)),
+    );
+    env.set(
+        "symbol?".to_string(),
+        Rc::new(Value::Builtin(builtin_is_symbol)),
+    );
+    env.set(
+        "gensym".to_string(),
+        Rc::new(Value::Builtin(builtin_gensym)),
+    );
+    env.set("eval".to_string(), Rc::new(Value::Builtin(builtin_eval)));
+    env.set("error".to_string(), Rc::new(Value::Builtin(builtin_error)));
 }
 
 type Arguments = Vec<Rc<Value>>;
 type Return = Result<Rc<Value>, InterpreterError>;
 
 // Name: "=".
-fn builtin_equals(args: Arguments) -> Return {
-    if args.len() != 2 {
-        return Err(InterpreterError::BuiltinArgumentError {
-            name: "=",
-            got: args.len(),
-            takes: "2",
+fn builtin_equals(_: &mut Interpreter, args: Arguments) -> Return {
+    // Numbers are equal if they have the same value, like in `compare_chain`, and booleans,
+    // strings, symbols and nil if they are the same. Any other argument results in `#f`.
+    let is_comparable = args.iter().all(|arg| {
+        matches!(
+            **arg,
+            Value::Integer(_)
+                | Value::Float(_)
+                | Value::Bool(_)
+                | Value::String(_)
+                | Value::Symbol(_)
+                | Value::Nil
+        )
+    });
+    let result = is_comparable
+        && args.windows(2).all(|pair| match (&*pair[0], &*pair[1]) {
+            (Value::Bool(l), Value::Bool(r)) => l == r,
+            (Value::String(l), Value::String(r)) => l == r,
+            (Value::Symbol(l), Value::Symbol(r)) => l == r,
+            (Value::Nil, Value::Nil) => true,
+            (l, r) => compare_numbers(l, r) == Some(Ordering::Equal),
         });
+    Ok(Rc::new(Value::Bool(result)))
+}
+
+// Name: "+".
+fn builtin_add(_: &mut Interpreter, args: Arguments) -> Return {
+    match numeric_arguments("+", &args)? {
+        Numbers::Integers(operands) => {
+            let sum = fold_integers(Integer::from(0), &operands, integer::checked_add);
+            checked_result("+", sum, &operands)
+        }
+        Numbers::Floats(operands) => Ok(Rc::new(Value::Float(operands.iter().sum()))),
     }
+}
 
-    let equal = match (&*args[0], &*args[1]) {
-        (Value::Integer(l), Value::Integer(r)) if l == r => true,
-        _ => false,
-    };
+// Name: "-".
+fn builtin_sub(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("-", &args, 1, None, "at least 1")?;
+    // With one argument, the argument is negated.
+    match numeric_arguments("-", &args)? {
+        Numbers::Integers(operands) => {
+            let difference = if operands.len() == 1 {
+                fold_integers(Integer::from(0), &operands, integer::checked_sub)
+            } else {
+                let first = operands[0].to_owned();
+                fold_integers(first, &operands[1..], integer::checked_sub)
+            };
+            checked_result("-", difference, &operands)
+        }
+        Numbers::Floats(operands) => {
+            let difference = if operands.len() == 1 {
+                -operands[0]
+            } else {
+                operands[1..].iter().fold(operands[0], |l, r| l - r)
+            };
+            Ok(Rc::new(Value::Float(difference)))
+        }
+    }
+}
 
-    Ok(Rc::new(Value::Integer(if equal { 1 } else { 0 })))
+// Name: "*".
+fn builtin_mul(_: &mut Interpreter, args: Arguments) -> Return {
+    match numeric_arguments("*", &args)? {
+        Numbers::Integers(operands) => {
+            let product = fold_integers(Integer::from(1), &operands, integer::checked_mul);
+            checked_result("*", product, &operands)
+        }
+        Numbers::Floats(operands) => Ok(Rc::new(Value::Float(operands.iter().product()))),
+    }
 }
 
-// Name: "+".
-fn builtin_add(args: Arguments) -> Return {
-    if args.len() != 2 {
+// Name: "print".
+fn builtin_print(_: &mut Interpreter, args: Arguments) -> Return {
+    for (i, arg) in args.iter().enumerate() {
+        if i != 0 {
+            print!(" ");
+        }
+        print!("{}", arg);
+    }
+    println!();
+    Ok(Rc::new(Value::Integer(Integer::from(0))))
+}
+
+// Name: ">".
+fn builtin_is_greater_than(_: &mut Interpreter, args: Arguments) -> Return {
+    compare_chain(&args, |ordering| ordering == Ordering::Greater)
+}
+
+// Name "<".
+fn builtin_is_less_than(_: &mut Interpreter, args: Arguments) -> Return {
+    compare_chain(&args, |ordering| ordering == Ordering::Less)
+}
+
+/// Check that a built-in function got at least `min` arguments, and at most `max` arguments if
+/// `max` is given. `takes` describes the number of arguments the function takes.
+fn check_argument_count(
+    name: &'static str,
+    args: &[Rc<Value>],
+    min: usize,
+    max: Option<usize>,
+    takes: &'static str,
+) -> Result<(), InterpreterError> {
+    if args.len() < min || max.map(|max| args.len() > max) == Some(true) {
         return Err(InterpreterError::BuiltinArgumentError {
-            name: "+",
+            name,
             got: args.len(),
-            takes: "2",
+            takes,
         });
     }
+    Ok(())
+}
 
-    let value = match (&*args[0], &*args[1]) {
-        (Value::Integer(l), Value::Integer(r)) => Value::Integer(l + r),
-        (Value::Integer(_), invalid) => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "+",
+/// Get the arguments of a built-in function that only takes integers.
+fn integer_arguments<'a>(
+    name: &'static str,
+    args: &'a [Rc<Value>],
+) -> Result<Vec<&'a Integer>, InterpreterError> {
+    args.iter()
+        .map(|arg| match &**arg {
+            Value::Integer(v) => Ok(v),
+            invalid => Err(InterpreterError::BuiltinTypeError {
+                name,
                 expected: "int",
                 found: invalid.type_name(),
-            })
-        }
-        (invalid, Value::Integer(_)) => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "+",
-                expected: "int",
+            }),
+        })
+        .collect()
+}
+
+/// Get the argument of a built-in function that takes one integer.
+fn one_integer<'a>(
+    name: &'static str,
+    args: &'a [Rc<Value>],
+) -> Result<&'a Integer, InterpreterError> {
+    check_argument_count(name, args, 1, Some(1), "1")?;
+    Ok(integer_arguments(name, args)?[0])
+}
+
+/// Get the arguments of a built-in function that takes two integers.
+fn two_integers<'a>(
+    name: &'static str,
+    args: &'a [Rc<Value>],
+) -> Result<(&'a Integer, &'a Integer), InterpreterError> {
+    check_argument_count(name, args, 2, Some(2), "2")?;
+    let operands = integer_arguments(name, args)?;
+    Ok((operands[0], operands[1]))
+}
+
+/// Get the arguments of a built-in function that only takes strings.
+fn string_arguments<'a>(
+    name: &'static str,
+    args: &'a [Rc<Value>],
+) -> Result<Vec<&'a str>, InterpreterError> {
+    args.iter()
+        .map(|arg| match &**arg {
+            Value::String(s) => Ok(s.as_str()),
+            invalid => Err(InterpreterError::BuiltinTypeError {
+                name,
+                expected: "string",
                 found: invalid.type_name(),
-            })
-        }
-        _ => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "+",
-                expected: "two integers",
-                found: "something non-integer",
-            })
+            }),
+        })
+        .collect()
+}
+
+/// Combine `initial` with every operand from left to right, using a checked integer operation.
+/// Returns `None` if any intermediate result does not fit in an integer.
+fn fold_integers(
+    initial: Integer,
+    operands: &[&Integer],
+    operation: fn(&Integer, &Integer) -> Option<Integer>,
+) -> Option<Integer> {
+    operands.iter().try_fold(initial, |l, r| operation(&l, r))
+}
+
+/// Returns `#t` if every argument is a number and `holds` is true for the ordering of every two
+/// adjacent arguments, and `#f` otherwise. Any argument that is not a number results in `#f`, even
+/// if there are fewer than two arguments; zero or one number always results in `#t`.
+///
+/// An integer and a float are compared by converting the integer to a float. NaN is not ordered
+/// with respect to any number, not even itself, so every comparison with NaN results in `#f`.
+fn compare_chain(args: &[Rc<Value>], holds: impl Fn(Ordering) -> bool) -> Return {
+    let is_number = args.iter().all(|arg| to_float(arg).is_some());
+    let result = is_number
+        && args
+            .windows(2)
+            .all(|pair| compare_numbers(&pair[0], &pair[1]).map(&holds) == Some(true));
+    Ok(Rc::new(Value::Bool(result)))
+}
+
+/// Compare two numbers, returning `None` if they are not ordered.
+fn compare_numbers(l: &Value, r: &Value) -> Option<Ordering> {
+    match (l, r) {
+        (Value::Integer(l), Value::Integer(r)) => Some(l.cmp(r)),
+        _ => to_float(l)?.partial_cmp(&to_float(r)?),
+    }
+}
+
+/// Convert a number to a float, returning `None` if the value is not a number.
+fn to_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(v) => Some(integer::to_f64(v)),
+        Value::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// The arguments of a built-in function that only takes numbers. If any argument is a float,
+/// every argument is converted to a float.
+enum Numbers<'a> {
+    Integers(Vec<&'a Integer>),
+    Floats(Vec<f64>),
+}
+
+/// Get the arguments of a built-in function that only takes numbers.
+fn numeric_arguments<'a>(
+    name: &'static str,
+    args: &'a [Rc<Value>],
+) -> Result<Numbers<'a>, InterpreterError> {
+    let mut is_float = false;
+    for arg in args {
+        match &**arg {
+            Value::Integer(_) => {}
+            Value::Float(_) => is_float = true,
+            invalid => {
+                return Err(InterpreterError::BuiltinTypeError {
+                    name,
+                    expected: "number",
+                    found: invalid.type_name(),
+                })
+            }
         }
-    };
-    Ok(Rc::new(value))
+    }
+
+    if is_float {
+        Ok(Numbers::Floats(
+            args.iter().filter_map(|arg| to_float(arg)).collect(),
+        ))
+    } else {
+        Ok(Numbers::Integers(integer_arguments(name, args)?))
+    }
 }
 
-// Name: "-".
-fn builtin_sub(args: Arguments) -> Return {
-    if args.len() != 2 {
-        return Err(InterpreterError::BuiltinArgumentError {
-            name: "-",
-            got: args.len(),
-            takes: "2",
-        });
+/// Get the arguments of a built-in function that takes two numbers.
+fn two_numbers<'a>(
+    name: &'static str,
+    args: &'a [Rc<Value>],
+) -> Result<Numbers<'a>, InterpreterError> {
+    check_argument_count(name, args, 2, Some(2), "2")?;
+    numeric_arguments(name, args)
+}
+
+/// Get the elements of the argument `arg` of a built-in function, which must be a list.
+fn list_elements(name: &'static str, arg: &Value) -> Result<Vec<Rc<Value>>, InterpreterError> {
+    arg.list_elements()
+        .ok_or_else(|| InterpreterError::BuiltinTypeError {
+            name,
+            expected: "list",
+            found: arg.type_name(),
+        })
+}
+
+/// Turn the result of a checked integer operation of a built-in function into a value, returning
+/// `InterpreterError::ArithmeticOverflow` if there is no result.
+fn checked_result(name: &'static str, result: Option<Integer>, operands: &[&Integer]) -> Return {
+    match result {
+        Some(v) => Ok(Rc::new(Value::Integer(v))),
+        None => Err(InterpreterError::ArithmeticOverflow {
+            name,
+            operands: operands.iter().map(|v| (*v).to_owned()).collect(),
+        }),
     }
+}
 
-    let value = match (&*args[0], &*args[1]) {
-        (Value::Integer(l), Value::Integer(r)) => Value::Integer(l - r),
-        (Value::Integer(_), invalid) => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "-",
-                expected: "int",
-                found: invalid.type_name(),
-            })
+// Name: "/".
+fn builtin_div(_: &mut Interpreter, args: Arguments) -> Return {
+    match two_numbers("/", &args)? {
+        Numbers::Integers(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            if integer::is_zero(r) {
+                return Err(InterpreterError::DivisionByZero { name: "/" });
+            }
+            checked_result("/", integer::checked_div(l, r), &[l, r])
         }
-        (invalid, Value::Integer(_)) => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "-",
-                expected: "int",
-                found: invalid.type_name(),
-            })
+        Numbers::Floats(operands) => Ok(Rc::new(Value::Float(operands[0] / operands[1]))),
+    }
+}
+
+// Name: "mod".
+fn builtin_mod(_: &mut Interpreter, args: Arguments) -> Return {
+    match two_numbers("mod", &args)? {
+        Numbers::Integers(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            if integer::is_zero(r) {
+                return Err(InterpreterError::DivisionByZero { name: "mod" });
+            }
+            checked_result("mod", integer::checked_mod(l, r), &[l, r])
         }
-        _ => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "-",
-                expected: "two integers",
-                found: "something non-integer",
-            })
+        Numbers::Floats(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            let remainder = l % r;
+            if remainder != 0.0 && (remainder < 0.0) != (r < 0.0) {
+                Ok(Rc::new(Value::Float(remainder + r)))
+            } else {
+                Ok(Rc::new(Value::Float(remainder)))
+            }
         }
-    };
-    Ok(Rc::new(value))
+    }
 }
 
-// Name: "*".
-fn builtin_mul(args: Arguments) -> Return {
-    if args.len() != 2 {
-        return Err(InterpreterError::BuiltinArgumentError {
-            name: "*",
-            got: args.len(),
-            takes: "2",
-        });
+// Name: "rem".
+fn builtin_rem(_: &mut Interpreter, args: Arguments) -> Return {
+    match two_numbers("rem", &args)? {
+        Numbers::Integers(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            if integer::is_zero(r) {
+                return Err(InterpreterError::DivisionByZero { name: "rem" });
+            }
+            checked_result("rem", integer::checked_rem(l, r), &[l, r])
+        }
+        Numbers::Floats(operands) => Ok(Rc::new(Value::Float(operands[0] % operands[1]))),
     }
+}
 
-    let value = match (&*args[0], &*args[1]) {
-        (Value::Integer(l), Value::Integer(r)) => Value::Integer(l * r),
-        (Value::Integer(_), invalid) => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "*",
-                expected: "int",
-                found: invalid.type_name(),
-            })
+// Name: "abs".
+fn builtin_abs(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("abs", &args, 1, Some(1), "1")?;
+    match numeric_arguments("abs", &args)? {
+        Numbers::Integers(operands) => {
+            checked_result("abs", integer::checked_abs(operands[0]), &operands)
         }
-        (invalid, Value::Integer(_)) => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "*",
-                expected: "int",
-                found: invalid.type_name(),
-            })
+        Numbers::Floats(operands) => Ok(Rc::new(Value::Float(operands[0].abs()))),
+    }
+}
+
+// Name: "min".
+fn builtin_min(_: &mut Interpreter, args: Arguments) -> Return {
+    match two_numbers("min", &args)? {
+        Numbers::Integers(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            Ok(Rc::new(Value::Integer(
+                if l <= r { l } else { r }.to_owned(),
+            )))
+        }
+        Numbers::Floats(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            // If either float is NaN, the result is NaN.
+            Ok(Rc::new(Value::Float(if l.is_nan() || l <= r {
+                l
+            } else {
+                r
+            })))
+        }
+    }
+}
+
+// Name: "max".
+fn builtin_max(_: &mut Interpreter, args: Arguments) -> Return {
+    match two_numbers("max", &args)? {
+        Numbers::Integers(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            Ok(Rc::new(Value::Integer(
+                if l >= r { l } else { r }.to_owned(),
+            )))
+        }
+        Numbers::Floats(operands) => {
+            let (l, r) = (operands[0], operands[1]);
+            // If either float is NaN, the result is NaN.
+            Ok(Rc::new(Value::Float(if l.is_nan() || l >= r {
+                l
+            } else {
+                r
+            })))
         }
-        _ => {
-            return Err(InterpreterError::BuiltinTypeError {
-                name: "*",
-                expected: "two integers",
-                found: "?",
+    }
+}
+
+// Name: "pow".
+fn builtin_pow(_: &mut Interpreter, args: Arguments) -> Return {
+    match two_numbers("pow", &args)? {
+        Numbers::Integers(operands) => {
+            let (base, exponent) = (operands[0], operands[1]);
+            if integer::is_negative(exponent) {
+                return Err(InterpreterError::BuiltinValueError {
+                    name: "pow",
+                    description: format!("integer exponent {} is negative", exponent),
+                });
+            }
+            checked_result(
+                "pow",
+                integer::checked_pow(base, exponent),
+                &[base, exponent],
+            )
+        }
+        Numbers::Floats(operands) => Ok(Rc::new(Value::Float(operands[0].powf(operands[1])))),
+    }
+}
+
+// Name: "gcd".
+fn builtin_gcd(_: &mut Interpreter, args: Arguments) -> Return {
+    let (l, r) = two_integers("gcd", &args)?;
+    checked_result("gcd", integer::checked_gcd(l, r), &[l, r])
+}
+
+// Name: "string-length".
+fn builtin_string_length(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("string-length", &args, 1, Some(1), "1")?;
+    let s = string_arguments("string-length", &args)?[0];
+    Ok(Rc::new(Value::Integer(Integer::from(
+        s.chars().count() as i64
+    ))))
+}
+
+// Name: "concat".
+fn builtin_concat(_: &mut Interpreter, args: Arguments) -> Return {
+    let strings = string_arguments("concat", &args)?;
+    Ok(Rc::new(Value::String(strings.concat())))
+}
+
+// Name: "substring".
+fn builtin_substring(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("substring", &args, 3, Some(3), "3")?;
+    let s = string_arguments("substring", &args[..1])?[0];
+    let indices = integer_arguments("substring", &args[1..])?;
+
+    // The indices count characters, not bytes.
+    let length = s.chars().count();
+    match (integer::to_usize(indices[0]), integer::to_usize(indices[1])) {
+        (Some(start), Some(end)) if start <= end && end <= length => Ok(Rc::new(Value::String(
+            s.chars().skip(start).take(end - start).collect(),
+        ))),
+        _ => Err(InterpreterError::BuiltinValueError {
+            name: "substring",
+            description: format!(
+                "range {} to {} is out of bounds for a string of length {}",
+                indices[0], indices[1], length
+            ),
+        }),
+    }
+}
+
+// Name: "string->int".
+fn builtin_string_to_int(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("string->int", &args, 1, Some(1), "1")?;
+    let s = string_arguments("string->int", &args)?[0];
+    match s.parse() {
+        Ok(v) => Ok(Rc::new(Value::Integer(v))),
+        Err(_) => {
+            // Like an integer literal, a string of digits can only fail to parse if it is too
+            // large.
+            let digits = s
+                .strip_prefix('-')
+                .or_else(|| s.strip_prefix('+'))
+                .unwrap_or(s);
+            let description = if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                format!("{:?} is too large for an integer", s)
+            } else {
+                format!("{:?} is not an integer", s)
+            };
+            Err(InterpreterError::BuiltinValueError {
+                name: "string->int",
+                description,
             })
         }
-    };
-    Ok(Rc::new(value))
+    }
 }
 
-// Name: "print".
-fn builtin_print(args: Arguments) -> Return {
-    for (i, arg) in args.iter().enumerate() {
-        if i != 0 {
-            print!(" ");
+// Name: "int->string".
+fn builtin_int_to_string(_: &mut Interpreter, args: Arguments) -> Return {
+    let v = one_integer("int->string", &args)?;
+    Ok(Rc::new(Value::String(v.to_string())))
+}
+
+// Name: "cons".
+fn builtin_cons(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("cons", &args, 2, Some(2), "2")?;
+    Ok(Rc::new(Value::Pair(args[0].clone(), args[1].clone())))
+}
+
+// Name: "car".
+fn builtin_car(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("car", &args, 1, Some(1), "1")?;
+    match &*args[0] {
+        Value::Pair(car, _) => Ok(car.clone()),
+        v => Err(InterpreterError::BuiltinTypeError {
+            name: "car",
+            expected: "pair",
+            found: v.type_name(),
+        }),
+    }
+}
+
+// Name: "cdr".
+fn builtin_cdr(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("cdr", &args, 1, Some(1), "1")?;
+    match &*args[0] {
+        Value::Pair(_, cdr) => Ok(cdr.clone()),
+        v => Err(InterpreterError::BuiltinTypeError {
+            name: "cdr",
+            expected: "pair",
+            found: v.type_name(),
+        }),
+    }
+}
+
+// Name: "list".
+fn builtin_list(_: &mut Interpreter, args: Arguments) -> Return {
+    Ok(Value::list(args))
+}
+
+// Name: "null?".
+fn builtin_is_null(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("null?", &args, 1, Some(1), "1")?;
+    Ok(Rc::new(Value::Bool(matches!(*args[0], Value::Nil))))
+}
+
+// Name: "length".
+fn builtin_length(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("length", &args, 1, Some(1), "1")?;
+    let elements = list_elements("length", &args[0])?;
+    Ok(Rc::new(Value::Integer(
+        Integer::from(elements.len() as i64),
+    )))
+}
+
+// Name: "append".
+fn builtin_append(_: &mut Interpreter, args: Arguments) -> Return {
+    let mut elements = Vec::new();
+    for arg in &args {
+        elements.extend(list_elements("append", arg)?);
+    }
+    Ok(Value::list(elements))
+}
+
+// Name: "reverse".
+fn builtin_reverse(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("reverse", &args, 1, Some(1), "1")?;
+    let mut elements = list_elements("reverse", &args[0])?;
+    elements.reverse();
+    Ok(Value::list(elements))
+}
+
+// Name: "map".
+fn builtin_map(interpreter: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("map", &args, 2, Some(2), "2")?;
+    let mut elements = Vec::new();
+    for element in list_elements("map", &args[1])? {
+        elements.push(interpreter.apply(&args[0], vec![element])?);
+    }
+    Ok(Value::list(elements))
+}
+
+// Name: "filter".
+fn builtin_filter(interpreter: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("filter", &args, 2, Some(2), "2")?;
+    let mut elements = Vec::new();
+    for element in list_elements("filter", &args[1])? {
+        let keep = interpreter.apply(&args[0], vec![element.clone()])?;
+        if interpreter.is_true(&keep, interpreter.current_position())? {
+            elements.push(element);
         }
-        print!("{}", arg);
     }
-    println!();
-    Ok(Rc::new(Value::Integer(0)))
+    Ok(Value::list(elements))
 }
 
-// Name: ">".
-fn builtin_is_greater_than(args: Arguments) -> Return {
-    if args.len() != 2 {
-        return Err(InterpreterError::BuiltinArgumentError {
-            name: ">",
-            got: args.len(),
-            takes: "2",
-        });
+// Name: "fold".
+fn builtin_fold(interpreter: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("fold", &args, 3, Some(3), "3")?;
+    let mut accumulator = args[1].clone();
+    for element in list_elements("fold", &args[2])? {
+        accumulator = interpreter.apply(&args[0], vec![accumulator, element])?;
     }
+    Ok(accumulator)
+}
 
-    let is_greater = match (&*args[0], &*args[1]) {
-        (Value::Integer(l), Value::Integer(r)) => l > r,
-        _ => false,
-    };
-    Ok(Rc::new(Value::Integer(if is_greater { 1 } else { 0 })))
+// Name: "box".
+fn builtin_box(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("box", &args, 1, Some(1), "1")?;
+    Ok(Rc::new(Value::Box(RefCell::new(args[0].clone()))))
 }
 
-// Name "<".
-fn builtin_is_less_than(args: Arguments) -> Return {
-    if args.len() != 2 {
-        return Err(InterpreterError::BuiltinArgumentError {
-            name: "<",
-            got: args.len(),
-            takes: "2",
-        });
+// Name: "unbox".
+fn builtin_unbox(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("unbox", &args, 1, Some(1), "1")?;
+    match &*args[0] {
+        Value::Box(contents) => Ok(contents.borrow().clone()),
+        v => Err(InterpreterError::BuiltinTypeError {
+            name: "unbox",
+            expected: "box",
+            found: v.type_name(),
+        }),
+    }
+}
+
+// Name: "set-box!".
+fn builtin_set_box(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("set-box!", &args, 2, Some(2), "2")?;
+    match &*args[0] {
+        Value::Box(contents) => {
+            contents.replace(args[1].clone());
+            Ok(Rc::new(Value::Integer(Integer::from(0))))
+        }
+        v => Err(InterpreterError::BuiltinTypeError {
+            name: "set-box!",
+            expected: "box",
+            found: v.type_name(),
+        }),
     }
+}
+
+// Name: "string->symbol".
+fn builtin_string_to_symbol(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("string->symbol", &args, 1, Some(1), "1")?;
+    let s = string_arguments("string->symbol", &args)?[0];
+    Ok(Rc::new(Value::Symbol(s.to_string())))
+}
+
+// Name: "symbol->string".
+fn builtin_symbol_to_string(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("symbol->string", &args, 1, Some(1), "1")?;
+    match &*args[0] {
+        Value::Symbol(name) => Ok(Rc::new(Value::String(name.clone()))),
+        v => Err(InterpreterError::BuiltinTypeError {
+            name: "symbol->string",
+            expected: "symbol",
+            found: v.type_name(),
+        }),
+    }
+}
+
+// Name: "symbol?".
+fn builtin_is_symbol(_: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("symbol?", &args, 1, Some(1), "1")?;
+    Ok(Rc::new(Value::Bool(matches!(*args[0], Value::Symbol(_)))))
+}
+
+// Name: "gensym".
+fn builtin_gensym(interpreter: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("gensym", &args, 0, Some(0), "0")?;
+    // '[' cannot be part of an identifier, so the symbol is different from every identifier that
+    // is written in the code.
+    interpreter.symbols += 1;
+    Ok(Rc::new(Value::Symbol(format!(
+        "#[g{}]",
+        interpreter.symbols
+    ))))
+}
+
+// Name: "eval".
+fn builtin_eval(interpreter: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("eval", &args, 1, Some(1), "1")?;
+    // The code is evaluated as if it were written in place of the call to `eval`, but in the
+    // top-level environment of the file that contains the call.
+    let expression = unquote(&args[0], interpreter.current_position())?;
+    let env = interpreter.builtin_env.clone();
+    interpreter.eval_in_file(expression, &env)
+}
 
-    let is_less = match (&*args[0], &*args[1]) {
-        (Value::Integer(l), Value::Integer(r)) => l < r,
-        _ => false,
-    };
-    Ok(Rc::new(Value::Integer(if is_less { 1 } else { 0 })))
+// Name: "error".
+fn builtin_error(interpreter: &mut Interpreter, args: Arguments) -> Return {
+    check_argument_count("error", &args, 1, Some(2), "1 or 2")?;
+    let message = string_arguments("error", &args[..1])?[0].to_string();
+    Err(InterpreterError::UserError {
+        message,
+        value: args.get(1).cloned().unwrap_or_else(|| Rc::new(Value::Nil)),
+        position: interpreter.current_position(),
+    })
 }
@@ -1,12 +1,28 @@
 use crate::interpreter::Value;
 use crate::Identifier;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::mem;
 use std::rc::Rc;
 
 /// The `Environment` contains all variable and function bindings.
-#[derive(Default)]
+///
+/// An `Environment` is a chain of frames: looking up a variable searches the innermost frame
+/// first, and then its parents. Cloning an `Environment` is cheap, and the clone shares its
+/// frames with the original, which is how functions capture the environment they are defined in.
+#[derive(Clone, Default)]
 pub struct Environment {
-    env: HashMap<Identifier, Rc<Value>>,
+    frame: Rc<Frame>,
+}
+
+/// A single frame of bindings in an `Environment`.
+#[derive(Default)]
+struct Frame {
+    /// The bindings in this frame.
+    env: RefCell<HashMap<Identifier, Rc<Value>>>,
+    /// The enclosing frame, if any.
+    parent: Option<Environment>,
 }
 
 impl Environment {
@@ -15,20 +31,79 @@ impl Environment {
         Environment::default()
     }
 
-    /// Get a reference to a variable from the `Environment`.
+    /// Create a new, empty `Environment` whose enclosing environment is `self`.
+    pub fn child(&self) -> Environment {
+        Environment {
+            frame: Rc::new(Frame {
+                env: RefCell::new(HashMap::new()),
+                parent: Some(self.clone()),
+            }),
+        }
+    }
+
+    /// Get a reference to a variable from the `Environment`, searching the enclosing frames if
+    /// it is not bound in the innermost frame.
     pub fn get(&self, key: &Identifier) -> Option<Rc<Value>> {
-        self.env.get(key).map(Clone::clone)
+        if let Some(value) = self.frame.env.borrow().get(key) {
+            return Some(value.clone());
+        }
+        self.frame
+            .parent
+            .as_ref()
+            .and_then(|parent| parent.get(key))
+    }
+
+    /// Take a value from the innermost frame of the `Environment`, getting ownership of the value
+    /// and removing the variable from the frame.
+    pub fn take(&self, key: &Identifier) -> Option<Rc<Value>> {
+        self.frame.env.borrow_mut().remove(key)
     }
 
-    /// Take a value from the `Environment`, getting ownership of the value and removing the
-    /// variable from the `Environment`.
-    pub fn take(&mut self, key: &Identifier) -> Option<Rc<Value>> {
-        self.env.remove(key)
+    /// Assign a new value to a variable in the innermost frame that binds it, and return the
+    /// previous value. If no frame binds the variable, nothing is changed, and `None` is
+    /// returned.
+    pub fn assign(&self, key: &Identifier, value: Rc<Value>) -> Option<Rc<Value>> {
+        if let Some(previous) = self.frame.env.borrow_mut().get_mut(key) {
+            return Some(mem::replace(previous, value));
+        }
+        self.frame
+            .parent
+            .as_ref()
+            .and_then(|parent| parent.assign(key, value))
     }
 
-    /// Set a variable in the `Environment` and returns the previous value of that variable, if
-    /// any.
-    pub fn set(&mut self, key: Identifier, value: Rc<Value>) -> Option<Rc<Value>> {
-        self.env.insert(key, value)
+    /// Get the bindings in the innermost frame of the `Environment`.
+    pub fn bindings(&self) -> Vec<(Identifier, Rc<Value>)> {
+        self.frame
+            .env
+            .borrow()
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
+    /// Get the enclosing environment of the innermost frame, if any.
+    pub fn parent(&self) -> Option<&Environment> {
+        self.frame.parent.as_ref()
+    }
+
+    /// Whether `self` and `other` have the same innermost frame, so that setting a variable in
+    /// one also sets it in the other.
+    pub fn ptr_eq(&self, other: &Environment) -> bool {
+        Rc::ptr_eq(&self.frame, &other.frame)
+    }
+
+    /// Set a variable in the innermost frame of the `Environment` and returns the previous value
+    /// of that variable in that frame, if any.
+    pub fn set(&self, key: Identifier, value: Rc<Value>) -> Option<Rc<Value>> {
+        self.frame.env.borrow_mut().insert(key, value)
+    }
+}
+
+impl fmt::Debug for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The bindings are not printed, because functions refer to the environment they are
+        // defined in, which would make printing recurse forever.
+        write!(f, "Environment {{ .. }}")
     }
 }
@@ -1,6 +1,15 @@
+use crate::integer::Integer;
+use crate::interpreter::Value;
+use crate::parser::ParseError;
 use crate::{Identifier, Position};
 use std::error;
 use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// The number of calls of a backtrace that are displayed.
+const BACKTRACE_DISPLAY_LENGTH: usize = 10;
 
 /// The error type of `Interpreter`.
 #[derive(Debug)]
@@ -34,6 +43,98 @@ pub enum InterpreterError {
         expected: &'static str,
         found: &'static str,
     },
+    /// An argument of a built-in function has the right type, but an invalid value.
+    BuiltinValueError {
+        name: &'static str,
+        description: String,
+    },
+    /// The result of an arithmetic operation in a built-in function does not fit in an integer.
+    ArithmeticOverflow {
+        name: &'static str,
+        operands: Vec<Integer>,
+    },
+    /// A built-in function divided by zero.
+    DivisionByZero { name: &'static str },
+    /// The maximum depth of the `Interpreter` was exceeded; see `Interpreter::set_max_depth`.
+    StackOverflow {
+        depth: usize,
+        position: Position,
+        /// The calls that were being evaluated, from the outermost to the innermost call.
+        backtrace: Vec<Call>,
+    },
+    /// A value that was turned into code does not form a valid expression.
+    InvalidCode {
+        error: Box<ParseError>,
+        position: Position,
+    },
+    /// An error happened while expanding a call to a macro.
+    MacroError {
+        /// The name of the macro.
+        name: Identifier,
+        /// The position of the call to the macro.
+        position: Position,
+        /// The error that happened.
+        error: Box<InterpreterError>,
+    },
+    /// An error happened while importing a file.
+    ImportError {
+        /// The file that was imported.
+        path: PathBuf,
+        /// The position of the import expression.
+        position: Position,
+        /// The error that happened.
+        error: Box<InterpreterError>,
+    },
+    /// An imported file was not found in the search path.
+    FileNotFound,
+    /// An imported file could not be read.
+    FileError(io::Error),
+    /// An imported file could not be parsed.
+    SyntaxError(Box<ParseError>),
+    /// A file imports itself, directly or through other files.
+    ImportCycle {
+        /// The files that import each other, each imported by the one before it.
+        files: Vec<PathBuf>,
+    },
+    /// An error raised by the built-in function `error`.
+    UserError {
+        message: String,
+        /// The value given with the message, or `Nil` if there is none.
+        value: Rc<Value>,
+        position: Position,
+    },
+}
+
+impl InterpreterError {
+    /// Turn the error into the value that the variable of a `catch` clause is bound to: a list of
+    /// a message and a value. For `InterpreterError::UserError`, these are the message and the
+    /// value given to `error`; for any other error, they are the description of the error and
+    /// `Nil`.
+    pub fn into_value(self) -> Rc<Value> {
+        let (message, value) = match self {
+            InterpreterError::UserError { message, value, .. } => (message, value),
+            error => (error.to_string(), Rc::new(Value::Nil)),
+        };
+        Value::list(vec![Rc::new(Value::String(message)), value])
+    }
+}
+
+/// A function call, as recorded in the backtrace of `InterpreterError::StackOverflow`.
+#[derive(Clone, Debug)]
+pub struct Call {
+    /// The name of the called function, if it was called by name.
+    pub name: Option<Identifier>,
+    /// The position of the call.
+    pub position: Position,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} at {}", name, self.position),
+            None => write!(f, "anonymous function at {}", self.position),
+        }
+    }
 }
 
 impl fmt::Display for InterpreterError {
@@ -75,6 +176,93 @@ impl fmt::Display for InterpreterError {
                 "built-in function {} expected argument of type {}, but got {}",
                 name, expected, found
             ),
+            BuiltinValueError { name, description } => write!(
+                f,
+                "built-in function {} got an invalid argument: {}",
+                name, description
+            ),
+            ArithmeticOverflow { name, operands } => {
+                write!(
+                    f,
+                    "integer overflow in built-in function {} with operands",
+                    name
+                )?;
+                for (i, operand) in operands.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {}", operand)?;
+                }
+                Ok(())
+            }
+            DivisionByZero { name } => write!(f, "division by zero in built-in function {}", name),
+            StackOverflow {
+                depth,
+                position,
+                backtrace,
+            } => {
+                write!(
+                    f,
+                    "maximum depth of {} exceeded at {}; most recent calls:",
+                    depth, position
+                )?;
+                for call in backtrace.iter().rev().take(BACKTRACE_DISPLAY_LENGTH) {
+                    write!(f, "\n    {}", call)?;
+                }
+                if backtrace.len() > BACKTRACE_DISPLAY_LENGTH {
+                    write!(
+                        f,
+                        "\n    ... and {} more",
+                        backtrace.len() - BACKTRACE_DISPLAY_LENGTH
+                    )?;
+                }
+                Ok(())
+            }
+            InvalidCode { error, position } => write!(f, "invalid code at {}: {}", position, error),
+            MacroError {
+                name,
+                position,
+                error,
+            } => write!(
+                f,
+                "error while expanding macro {} at {}: {}",
+                name, position, error
+            ),
+            ImportError {
+                path,
+                position,
+                error,
+            } => write!(
+                f,
+                "error while importing {} at {}: {}",
+                path.display(),
+                position,
+                error
+            ),
+            FileNotFound => write!(f, "file not found in the search path"),
+            FileError(error) => write!(f, "error while reading file: {}", error),
+            SyntaxError(error) => write!(f, "error while parsing: {}", error),
+            ImportCycle { files } => {
+                write!(f, "import cycle:")?;
+                for (i, file) in files.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, " ->")?;
+                    }
+                    write!(f, " {}", file.display())?;
+                }
+                Ok(())
+            }
+            UserError {
+                message,
+                value,
+                position,
+            } => {
+                write!(f, "error at {}: {}", position, message)?;
+                match **value {
+                    Value::Nil => Ok(()),
+                    _ => write!(f, " {}", value),
+                }
+            }
         }
     }
 }
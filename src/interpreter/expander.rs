@@ -0,0 +1,293 @@
+use crate::ast::{Datum, AST};
+use crate::interpreter::{Call, Interpreter, InterpreterError, Quoter, Value};
+use crate::{Identifier, Position};
+use std::rc::Rc;
+
+impl Interpreter {
+    /// Expand the macros in an expression, returning an expression without any macro calls.
+    ///
+    /// A `defmacro` expression defines its macro as soon as it is expanded, so the macro can be
+    /// used in the rest of the expression, and in every expression that is expanded later. A call
+    /// to a macro is replaced by the result of the macro, which is expanded in turn.
+    pub fn expand(&mut self, expression: AST) -> Result<AST, InterpreterError> {
+        self.expand_in(expression, &mut Vec::new())
+    }
+
+    /// Expand the macros in an expression, where `locals` contains the variables that are bound
+    /// by the expressions around it. A call to a variable in `locals` is not expanded, also when
+    /// there is a macro with its name, because the variable shadows the macro.
+    fn expand_in(
+        &mut self,
+        expression: AST,
+        locals: &mut Vec<Identifier>,
+    ) -> Result<AST, InterpreterError> {
+        use crate::ast::ASTType::*;
+
+        let position = expression.position;
+        let expand_box =
+            |interpreter: &mut Interpreter, expression: Box<AST>, locals: &mut Vec<_>| {
+                interpreter.expand_in(*expression, locals).map(Box::new)
+            };
+        let expand_all =
+            |interpreter: &mut Interpreter, expressions: Vec<AST>, locals: &mut Vec<_>| {
+                expressions
+                    .into_iter()
+                    .map(|expression| interpreter.expand_in(expression, locals))
+                    .collect::<Result<Vec<_>, _>>()
+            };
+        let expand_option =
+            |interpreter: &mut Interpreter, expression: Option<Box<AST>>, locals: &mut Vec<_>| {
+                expression
+                    .map(|expression| expand_box(interpreter, expression, locals))
+                    .transpose()
+            };
+        // The variables that are bound by an expression are only in `locals` while its body is
+        // expanded. When expanding results in an error, `locals` does not matter anymore.
+        let scope = locals.len();
+
+        let ast = match expression.ast {
+            Defmacro {
+                name,
+                arguments,
+                value,
+            } => {
+                locals.extend(arguments.iter().cloned());
+                let value = self.expand_in(*value, locals)?;
+                locals.truncate(scope);
+                // The macro runs in the file it is defined in, also when it is used while that
+                // file is being imported.
+                let transformer = Value::Function {
+                    name: Some(name.clone()),
+                    arguments: arguments.clone(),
+                    value: value.clone(),
+                    env: self.top_level_env(),
+                };
+                self.macros.insert(name.clone(), Rc::new(transformer));
+                Defmacro {
+                    name,
+                    arguments,
+                    value: Box::new(value),
+                }
+            }
+            FunctionCall {
+                function,
+                arguments,
+            } => {
+                // A local variable shadows a macro with the same name.
+                let transformer = match &function.ast {
+                    Identifier(name) if !locals.contains(name) => self
+                        .macros
+                        .get(name)
+                        .map(|transformer| (name.clone(), transformer.clone())),
+                    _ => None,
+                };
+                if let Some((name, transformer)) = transformer {
+                    return self.expand_macro(name, &transformer, &arguments, position, locals);
+                }
+                FunctionCall {
+                    function: expand_box(self, function, locals)?,
+                    arguments: expand_all(self, arguments, locals)?,
+                }
+            }
+            // A definition binds its name in the rest of the body it is in, so the name stays in
+            // `locals` until the expression that made that body is expanded.
+            Define {
+                name,
+                arguments: None,
+                value,
+            } => {
+                locals.push(name.clone());
+                Define {
+                    name,
+                    arguments: None,
+                    value: expand_box(self, value, locals)?,
+                }
+            }
+            Define {
+                name,
+                arguments: Some(arguments),
+                value,
+            } => {
+                locals.push(name.clone());
+                let scope = locals.len();
+                locals.extend(arguments.iter().cloned());
+                let value = expand_box(self, value, locals)?;
+                locals.truncate(scope);
+                Define {
+                    name,
+                    arguments: Some(arguments),
+                    value,
+                }
+            }
+            Set { name, value } => Set {
+                name,
+                value: expand_box(self, value, locals)?,
+            },
+            Lambda { arguments, value } => {
+                locals.extend(arguments.iter().cloned());
+                let value = expand_box(self, value, locals)?;
+                locals.truncate(scope);
+                Lambda { arguments, value }
+            }
+            Let { bindings, value } => {
+                let bindings = bindings
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, self.expand_in(value, locals)?)))
+                    .collect::<Result<Vec<_>, InterpreterError>>()?;
+                locals.extend(bindings.iter().map(|(name, _)| name.clone()));
+                let value = expand_box(self, value, locals)?;
+                locals.truncate(scope);
+                Let { bindings, value }
+            }
+            LetStar { bindings, value } => {
+                let bindings = bindings
+                    .into_iter()
+                    .map(|(name, value)| {
+                        let value = self.expand_in(value, locals)?;
+                        locals.push(name.clone());
+                        Ok((name, value))
+                    })
+                    .collect::<Result<Vec<_>, InterpreterError>>()?;
+                let value = expand_box(self, value, locals)?;
+                locals.truncate(scope);
+                LetStar { bindings, value }
+            }
+            Letrec { bindings, value } => {
+                locals.extend(bindings.iter().map(|(name, _)| name.clone()));
+                let bindings = bindings
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, self.expand_in(value, locals)?)))
+                    .collect::<Result<Vec<_>, InterpreterError>>()?;
+                let value = expand_box(self, value, locals)?;
+                locals.truncate(scope);
+                Letrec { bindings, value }
+            }
+            If {
+                condition,
+                consequence,
+                alternative,
+            } => If {
+                condition: expand_box(self, condition, locals)?,
+                consequence: expand_box(self, consequence, locals)?,
+                alternative: expand_box(self, alternative, locals)?,
+            },
+            Cond {
+                clauses,
+                alternative,
+            } => Cond {
+                clauses: clauses
+                    .into_iter()
+                    .map(|(condition, value)| {
+                        Ok((
+                            self.expand_in(condition, locals)?,
+                            self.expand_in(value, locals)?,
+                        ))
+                    })
+                    .collect::<Result<_, InterpreterError>>()?,
+                alternative: expand_option(self, alternative, locals)?,
+            },
+            Case {
+                key,
+                clauses,
+                alternative,
+            } => Case {
+                key: expand_box(self, key, locals)?,
+                clauses: clauses
+                    .into_iter()
+                    .map(|(values, value)| Ok((values, self.expand_in(value, locals)?)))
+                    .collect::<Result<_, InterpreterError>>()?,
+                alternative: expand_option(self, alternative, locals)?,
+            },
+            Begin(expressions) => Begin(expand_all(self, expressions, locals)?),
+            And(operands) => And(expand_all(self, operands, locals)?),
+            Or(operands) => Or(expand_all(self, operands, locals)?),
+            Not(operand) => Not(expand_box(self, operand, locals)?),
+            Try {
+                value,
+                name,
+                handler,
+            } => {
+                let value = expand_box(self, value, locals)?;
+                locals.push(name.clone());
+                let handler = expand_box(self, handler, locals)?;
+                locals.truncate(scope);
+                Try {
+                    value,
+                    name,
+                    handler,
+                }
+            }
+            Quasiquote(datum) => Quasiquote(self.expand_datum(datum, locals)?),
+            ast @ Identifier(_)
+            | ast @ Integer(_)
+            | ast @ Float(_)
+            | ast @ String(_)
+            | ast @ Bool(_)
+            | ast @ Quote(_)
+            | ast @ Import { .. } => ast,
+        };
+
+        Ok(AST { ast, position })
+    }
+
+    /// Expand the macros in the unquoted expressions of a datum, like `Interpreter::expand_in`.
+    fn expand_datum(
+        &mut self,
+        datum: Datum,
+        locals: &mut Vec<Identifier>,
+    ) -> Result<Datum, InterpreterError> {
+        Ok(match datum {
+            Datum::List(elements) => Datum::List(
+                elements
+                    .into_iter()
+                    .map(|element| self.expand_datum(element, locals))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Datum::Unquote(expression) => {
+                Datum::Unquote(Box::new(self.expand_in(*expression, locals)?))
+            }
+            datum => datum,
+        })
+    }
+
+    /// Expand a call at `position` to the macro `name`, whose function is `transformer`: the
+    /// function is called with the code of the arguments, and the code it returns is expanded
+    /// with the variables `locals` bound. The code of the arguments keeps the positions of the
+    /// arguments, and the rest of the code gets the position of the call.
+    /// Errors are returned as `InterpreterError::MacroError`, with the position of the call.
+    ///
+    /// The call is on the call stack while the result is expanded, so a macro that keeps
+    /// expanding to a call to itself results in `InterpreterError::StackOverflow`.
+    fn expand_macro(
+        &mut self,
+        name: Identifier,
+        transformer: &Value,
+        arguments: &[AST],
+        position: Position,
+        locals: &mut Vec<Identifier>,
+    ) -> Result<AST, InterpreterError> {
+        let depth = self.call_stack.len();
+        let call = Call {
+            name: Some(name.clone()),
+            position,
+        };
+
+        let mut quoter = Quoter::default();
+        let code = arguments
+            .iter()
+            .map(|argument| quoter.quote(argument))
+            .collect();
+        let result = self
+            .push_call(call, depth)
+            .and_then(|()| self.apply(transformer, code))
+            .and_then(|code| quoter.unquote(&code, position))
+            .map_err(|error| InterpreterError::MacroError {
+                name,
+                position,
+                error: Box::new(error),
+            })
+            .and_then(|expansion| self.expand_in(expansion, locals));
+        self.call_stack.truncate(depth);
+        result
+    }
+}
@@ -0,0 +1,234 @@
+use crate::ast::AST;
+use crate::interpreter::{builtins, Environment, Interpreter, InterpreterError, Value};
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::{Identifier, Position};
+use std::collections::HashMap;
+use std::fs;
+use std::iter;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A file that is imported.
+#[derive(Clone)]
+pub(super) struct Module {
+    /// `path` is the canonical path of the file.
+    path: PathBuf,
+    /// `env` is the environment that contains the definitions of the file.
+    env: Environment,
+    /// `imported` contains the variables that the file bound in `env` by importing other files,
+    /// which are not definitions of the file unless the file defines them again.
+    imported: HashMap<Identifier, Rc<Value>>,
+    /// `macros` contains the functions of the macros that the file defined, by name.
+    macros: HashMap<Identifier, Rc<Value>>,
+}
+
+impl Module {
+    /// Get the definitions of the file.
+    fn definitions(&self) -> Vec<(Identifier, Rc<Value>)> {
+        self.env
+            .bindings()
+            .into_iter()
+            .filter(|(name, value)| match self.imported.get(name) {
+                Some(imported) => !Rc::ptr_eq(value, imported),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+impl Interpreter {
+    /// Import the file at `path` for an import expression at `position`, and bind its
+    /// definitions in `env`: every definition, with the name of the file and a '/' before its
+    /// name, like `lib/f` for the definition `f` in `lib.tini`, or only `names`, as they are.
+    ///
+    /// A file is evaluated the first time it is imported, in an environment of its own; after
+    /// that, importing it again only binds its definitions. Errors are returned as
+    /// `InterpreterError::ImportError`, with the path of the file.
+    pub(super) fn import(
+        &mut self,
+        path: &str,
+        names: Option<&[Identifier]>,
+        env: &Environment,
+        position: Position,
+    ) -> Result<(), InterpreterError> {
+        let error = |path: &Path, error: InterpreterError| InterpreterError::ImportError {
+            path: path.to_path_buf(),
+            position,
+            error: Box::new(error),
+        };
+
+        let path = match self.resolve(Path::new(path)) {
+            Some(path) => path,
+            None => return Err(error(Path::new(path), InterpreterError::FileNotFound)),
+        };
+        let module = match self.modules.get(&path) {
+            Some(module) => module.clone(),
+            None => {
+                let module = self.load(&path).map_err(|e| error(&path, e))?;
+                self.modules.insert(path.clone(), module.clone());
+                module
+            }
+        };
+
+        let definitions = module.definitions();
+        let bindings = match names {
+            None => {
+                let namespace = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy())
+                    .unwrap_or_default();
+                definitions
+                    .into_iter()
+                    .map(|(name, value)| (format!("{}/{}", namespace, name), value))
+                    .collect()
+            }
+            Some(names) => {
+                let definitions = definitions.into_iter().collect::<HashMap<_, _>>();
+                let mut bindings = Vec::new();
+                for name in names {
+                    match definitions.get(name) {
+                        Some(value) => bindings.push((name.clone(), value.clone())),
+                        None => {
+                            let name = name.clone();
+                            let unknown = InterpreterError::UnknownVariable { name, position };
+                            return Err(error(&path, unknown));
+                        }
+                    }
+                }
+                bindings
+            }
+        };
+
+        // Names that a file binds by importing are not its definitions, so they are not
+        // imported from it in turn.
+        let importer = self
+            .importing
+            .last_mut()
+            .filter(|importer| importer.env.ptr_eq(env));
+        if let Some(importer) = importer {
+            importer.imported.extend(bindings.iter().cloned());
+        }
+        for (name, value) in bindings {
+            env.set(name, value);
+        }
+        Ok(())
+    }
+
+    /// Get the top-level environment of the file that is being imported, if any, or of the
+    /// `Interpreter` otherwise.
+    pub(super) fn top_level_env(&self) -> Environment {
+        match self.importing.last() {
+            Some(module) => module.env.clone(),
+            None => self.env.clone(),
+        }
+    }
+
+    /// Evaluate an expression in the top-level environment of the file that `env` is in, after
+    /// expanding its macros with the macros of that file. That is the file whose top-level
+    /// environment is the innermost one around `env`, or the file of the `Interpreter` if there
+    /// is none.
+    pub(super) fn eval_in_file(
+        &mut self,
+        expression: AST,
+        env: &Environment,
+    ) -> Result<Rc<Value>, InterpreterError> {
+        let mut frame = env.clone();
+        loop {
+            // The macros of the files that are being evaluated are already in use.
+            let evaluating = iter::once(&self.env)
+                .chain(self.importing.iter().map(|module| &module.env))
+                .any(|env| env.ptr_eq(&frame));
+            if evaluating {
+                break;
+            }
+            if let Some(module) = self
+                .modules
+                .values()
+                .find(|module| module.env.ptr_eq(&frame))
+            {
+                let path = module.path.clone();
+                let macros = mem::replace(&mut self.macros, module.macros.clone());
+                let result = self
+                    .expand(expression)
+                    .and_then(|expression| self.eval_in(expression, &frame));
+                let macros = mem::replace(&mut self.macros, macros);
+                if let Some(module) = self.modules.get_mut(&path) {
+                    module.macros = macros;
+                }
+                return result;
+            }
+            frame = match frame.parent() {
+                Some(parent) => parent.clone(),
+                None => self.env.clone(),
+            };
+        }
+
+        let expression = self.expand(expression)?;
+        self.eval_in(expression, &frame)
+    }
+
+    /// Find the file at `path`, which is relative to the directory of the file that is being
+    /// imported, if any, or to one of the directories of the search path, in that order. Returns
+    /// the canonical path of the file, or `None` if it is not found.
+    fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        let importing = self
+            .importing
+            .last()
+            .and_then(|module| module.path.parent())
+            .map(Path::to_path_buf);
+
+        importing
+            .into_iter()
+            .chain(self.search_path.iter().cloned())
+            .map(|directory| directory.join(path))
+            .find(|file| file.is_file())
+            .and_then(|file| file.canonicalize().ok())
+    }
+
+    /// Evaluate the file at `path`, which is canonical, and return it as a `Module`.
+    fn load(&mut self, path: &Path) -> Result<Module, InterpreterError> {
+        if let Some(i) = self.importing.iter().position(|module| module.path == path) {
+            let files = self.importing[i..]
+                .iter()
+                .map(|module| module.path.clone())
+                .chain(iter::once(path.to_path_buf()))
+                .collect();
+            return Err(InterpreterError::ImportCycle { files });
+        }
+
+        let input = fs::read_to_string(path).map_err(InterpreterError::FileError)?;
+
+        // The file only sees the built-in functions, and its definitions are made in a frame of
+        // their own, so they can be told apart from the built-in functions.
+        let builtins = Environment::new();
+        builtins::add_builtins_to_environment(&builtins);
+        let env = builtins.child();
+
+        // The file does not see the macros of the file that imports it either, and its own
+        // macros are only used in the file.
+        let macros = mem::take(&mut self.macros);
+        self.importing.push(Module {
+            path: path.to_path_buf(),
+            env: env.clone(),
+            imported: HashMap::new(),
+            macros: HashMap::new(),
+        });
+        let result = self.eval_file(&input, &env);
+        let module = self.importing.pop();
+        let macros = mem::replace(&mut self.macros, macros);
+        let module = module.expect("the module is pushed before the file is evaluated");
+        result.map(|()| Module { macros, ..module })
+    }
+
+    /// Evaluate every expression in `input`, the contents of a file, in `env`.
+    fn eval_file(&mut self, input: &str, env: &Environment) -> Result<(), InterpreterError> {
+        for expression in Parser::new(Lexer::new(input)) {
+            let expression = expression.map_err(|e| InterpreterError::SyntaxError(Box::new(e)))?;
+            let expression = self.expand(expression)?;
+            self.eval_in(expression, env)?;
+        }
+        Ok(())
+    }
+}
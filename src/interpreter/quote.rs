@@ -0,0 +1,340 @@
+use crate::ast::{ASTType, Datum, AST};
+use crate::interpreter::{InterpreterError, Value};
+use crate::parser::{ParseResult, Parser};
+use crate::token::{Token, TokenType};
+use crate::{Identifier, Position};
+use std::collections::HashMap;
+use std::iter;
+use std::rc::Rc;
+
+/// Turn an expression into code that is data: a literal becomes its value, an identifier or a
+/// keyword becomes a symbol, and any other expression becomes a list of its parts. For example,
+/// `(if (< x 0) "negative" x)` becomes a list of the symbol `if`, a list of the symbols `<` and
+/// `x` and the integer `0`, the string `"negative"` and the symbol `x`.
+pub fn quote(expression: &AST) -> Rc<Value> {
+    Quoter::default().quote(expression)
+}
+
+/// Turn code that is data back into an expression; this is the inverse of `quote`. The value is
+/// parsed as if it were written at `position`, so every part of the expression, and every error,
+/// has that position.
+pub fn unquote(code: &Value, position: Position) -> Result<AST, InterpreterError> {
+    Quoter::default().unquote(code, position)
+}
+
+/// A `Quoter` turns expressions into code like `quote`, and remembers the position of every
+/// expression it turned into code, so that the expression keeps its position when it is turned
+/// back into an expression. This is how the arguments of a macro keep their positions.
+#[derive(Default)]
+pub struct Quoter {
+    /// `positions` contains the code that was made, with the position of its expression, by the
+    /// address of the code. The code is kept, so that no other value can get its address.
+    positions: HashMap<*const Value, (Rc<Value>, Position)>,
+}
+
+impl Quoter {
+    /// Turn an expression into code, like `quote`.
+    pub fn quote(&mut self, expression: &AST) -> Rc<Value> {
+        let code = self.quote_ast(&expression.ast);
+        self.positions
+            .insert(Rc::as_ptr(&code), (code.clone(), expression.position));
+        code
+    }
+
+    /// Turn code back into an expression, like `unquote`. The parts of the code that this
+    /// `Quoter` made have the positions of their expressions, and the other parts have the
+    /// position of the code around them, or `position`.
+    pub fn unquote(&self, code: &Value, position: Position) -> Result<AST, InterpreterError> {
+        let mut tokens = Vec::new();
+        self.push_tokens(code, position, &mut tokens)?;
+
+        match Parser::from_tokens(tokens).parse_expression() {
+            ParseResult::Ok(expression) => Ok(expression),
+            ParseResult::Err(error) => Err(InterpreterError::InvalidCode {
+                error: Box::new(error),
+                position,
+            }),
+            ParseResult::Eof => unreachable!("a value is always turned into at least one token"),
+        }
+    }
+
+    /// Turn the kind of an expression into code; see `quote`.
+    fn quote_ast(&mut self, expression: &ASTType) -> Rc<Value> {
+        match expression {
+            ASTType::Define {
+                name,
+                arguments: None,
+                value,
+            } => Value::list(vec![
+                keyword(TokenType::Define),
+                symbol(name),
+                self.quote(value),
+            ]),
+            ASTType::Define {
+                name,
+                arguments: Some(arguments),
+                value,
+            } => {
+                let signature = iter::once(name).chain(arguments).map(symbol).collect();
+                Value::list(vec![
+                    keyword(TokenType::Define),
+                    Value::list(signature),
+                    self.quote(value),
+                ])
+            }
+            ASTType::Defmacro {
+                name,
+                arguments,
+                value,
+            } => {
+                let signature = iter::once(name).chain(arguments).map(symbol).collect();
+                Value::list(vec![
+                    keyword(TokenType::Defmacro),
+                    Value::list(signature),
+                    self.quote(value),
+                ])
+            }
+            ASTType::Import { path, names } => {
+                let path = Rc::new(Value::String(path.clone()));
+                match names {
+                    Some(names) => {
+                        let only = iter::once(Rc::new(Value::Symbol("only".to_string())))
+                            .chain(names.iter().map(symbol))
+                            .collect();
+                        Value::list(vec![keyword(TokenType::Import), path, Value::list(only)])
+                    }
+                    None => Value::list(vec![keyword(TokenType::Import), path]),
+                }
+            }
+            ASTType::Set { name, value } => Value::list(vec![
+                keyword(TokenType::Set),
+                symbol(name),
+                self.quote(value),
+            ]),
+            ASTType::Lambda { arguments, value } => Value::list(vec![
+                keyword(TokenType::Lambda),
+                Value::list(arguments.iter().map(symbol).collect()),
+                self.quote(value),
+            ]),
+            ASTType::Let { bindings, value } => Value::list(vec![
+                keyword(TokenType::Let),
+                self.bindings(bindings),
+                self.quote(value),
+            ]),
+            ASTType::LetStar { bindings, value } => Value::list(vec![
+                keyword(TokenType::LetStar),
+                self.bindings(bindings),
+                self.quote(value),
+            ]),
+            ASTType::Letrec { bindings, value } => Value::list(vec![
+                keyword(TokenType::Letrec),
+                self.bindings(bindings),
+                self.quote(value),
+            ]),
+            ASTType::If {
+                condition,
+                consequence,
+                alternative,
+            } => Value::list(vec![
+                keyword(TokenType::If),
+                self.quote(condition),
+                self.quote(consequence),
+                self.quote(alternative),
+            ]),
+            ASTType::Cond {
+                clauses,
+                alternative,
+            } => {
+                let clauses = clauses
+                    .iter()
+                    .map(|(condition, value)| {
+                        Value::list(vec![self.quote(condition), self.quote(value)])
+                    })
+                    .collect::<Vec<_>>();
+                Value::list(
+                    iter::once(keyword(TokenType::Cond))
+                        .chain(clauses)
+                        .chain(self.else_clause(alternative))
+                        .collect(),
+                )
+            }
+            ASTType::Case {
+                key,
+                clauses,
+                alternative,
+            } => {
+                let key = self.quote(key);
+                let clauses = clauses
+                    .iter()
+                    .map(|(values, value)| {
+                        let values = values
+                            .iter()
+                            .map(|v| Rc::new(Value::Integer(v.to_owned())))
+                            .collect();
+                        Value::list(vec![Value::list(values), self.quote(value)])
+                    })
+                    .collect::<Vec<_>>();
+                Value::list(
+                    vec![keyword(TokenType::Case), key]
+                        .into_iter()
+                        .chain(clauses)
+                        .chain(self.else_clause(alternative))
+                        .collect(),
+                )
+            }
+            ASTType::Begin(expressions) => self.form(TokenType::Begin, expressions),
+            ASTType::And(operands) => self.form(TokenType::And, operands),
+            ASTType::Or(operands) => self.form(TokenType::Or, operands),
+            ASTType::Not(operand) => {
+                Value::list(vec![keyword(TokenType::Not), self.quote(operand)])
+            }
+            ASTType::Try {
+                value,
+                name,
+                handler,
+            } => Value::list(vec![
+                keyword(TokenType::Try),
+                self.quote(value),
+                Value::list(vec![
+                    keyword(TokenType::Catch),
+                    symbol(name),
+                    self.quote(handler),
+                ]),
+            ]),
+            ASTType::Quote(datum) => {
+                Value::list(vec![keyword(TokenType::Quote), self.quote_datum(datum)])
+            }
+            ASTType::Quasiquote(datum) => Value::list(vec![
+                keyword(TokenType::Quasiquote),
+                self.quote_datum(datum),
+            ]),
+            ASTType::FunctionCall {
+                function,
+                arguments,
+            } => {
+                let function = self.quote(function);
+                Value::list(
+                    iter::once(function)
+                        .chain(arguments.iter().map(|argument| self.quote(argument)))
+                        .collect(),
+                )
+            }
+            ASTType::Identifier(name) => symbol(name),
+            ASTType::Integer(v) => Rc::new(Value::Integer(v.to_owned())),
+            ASTType::Float(v) => Rc::new(Value::Float(*v)),
+            ASTType::String(s) => Rc::new(Value::String(s.clone())),
+            ASTType::Bool(v) => Rc::new(Value::Bool(*v)),
+        }
+    }
+
+    /// Turn an expression that consists of a keyword followed by expressions into code.
+    fn form(&mut self, token: TokenType, expressions: &[AST]) -> Rc<Value> {
+        Value::list(
+            iter::once(keyword(token))
+                .chain(expressions.iter().map(|expression| self.quote(expression)))
+                .collect(),
+        )
+    }
+
+    /// Turn the bindings of a `let`, `let*` or `letrec` expression into code.
+    fn bindings(&mut self, bindings: &[(Identifier, AST)]) -> Rc<Value> {
+        Value::list(
+            bindings
+                .iter()
+                .map(|(name, value)| Value::list(vec![symbol(name), self.quote(value)]))
+                .collect(),
+        )
+    }
+
+    /// Turn the `else` clause of a `cond` or `case` expression into code, which is empty if there
+    /// is no `else` clause.
+    fn else_clause(&mut self, alternative: &Option<Box<AST>>) -> Vec<Rc<Value>> {
+        alternative
+            .iter()
+            .map(|value| Value::list(vec![keyword(TokenType::Else), self.quote(value)]))
+            .collect()
+    }
+
+    /// Turn a datum into the value that represents it. An unquoted expression becomes a list of
+    /// the symbol `unquote` and the expression.
+    fn quote_datum(&mut self, datum: &Datum) -> Rc<Value> {
+        Rc::new(match datum {
+            Datum::Symbol(name) => Value::Symbol(name.clone()),
+            Datum::Integer(v) => Value::Integer(v.to_owned()),
+            Datum::Float(v) => Value::Float(*v),
+            Datum::String(s) => Value::String(s.clone()),
+            Datum::Bool(v) => Value::Bool(*v),
+            Datum::List(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.quote_datum(element))
+                    .collect();
+                return Value::list(elements);
+            }
+            Datum::Unquote(expression) => {
+                return Value::list(vec![keyword(TokenType::Unquote), self.quote(expression)])
+            }
+        })
+    }
+
+    /// Push the `Token`s that `code` consists of onto `tokens`, at the position of `code` if this
+    /// `Quoter` made it, and at `position` otherwise.
+    fn push_tokens(
+        &self,
+        code: &Value,
+        position: Position,
+        tokens: &mut Vec<Token>,
+    ) -> Result<(), InterpreterError> {
+        let position = match self.positions.get(&(code as *const Value)) {
+            Some((_, position)) => *position,
+            None => position,
+        };
+        let token = match code {
+            Value::Integer(v) => TokenType::Integer(v.to_owned()),
+            Value::Float(v) => TokenType::Float(*v),
+            Value::String(s) => TokenType::String(s.clone()),
+            Value::Bool(v) => TokenType::Bool(*v),
+            Value::Symbol(name) => TokenType::identifier_or_keyword(name.clone()),
+            Value::Pair(_, _) | Value::Nil => {
+                let elements = match code.list_elements() {
+                    Some(elements) => elements,
+                    None => {
+                        return Err(InterpreterError::TypeError {
+                            expected: "list as code",
+                            found: code.type_name(),
+                            position,
+                        })
+                    }
+                };
+                tokens.push(Token {
+                    token: TokenType::LeftBracket,
+                    position,
+                });
+                for element in elements {
+                    self.push_tokens(&element, position, tokens)?;
+                }
+                TokenType::RightBracket
+            }
+            v => {
+                return Err(InterpreterError::TypeError {
+                    expected: "code",
+                    found: v.type_name(),
+                    position,
+                })
+            }
+        };
+
+        tokens.push(Token { token, position });
+        Ok(())
+    }
+}
+
+/// The symbol of a keyword.
+fn keyword(token: TokenType) -> Rc<Value> {
+    Rc::new(Value::Symbol(token.to_string()))
+}
+
+/// The symbol of an identifier.
+fn symbol(name: &Identifier) -> Rc<Value> {
+    Rc::new(Value::Symbol(name.clone()))
+}
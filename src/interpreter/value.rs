@@ -1,24 +1,49 @@
 use crate::ast::AST;
-use crate::interpreter::InterpreterError;
+use crate::integer::{self, Integer};
+use crate::interpreter::{Environment, Interpreter, InterpreterError};
 use crate::Identifier;
+use std::cell::RefCell;
 use std::fmt;
+use std::mem;
 use std::rc::Rc;
 
-/// The type of a built-in function.
-pub type BuiltinFunction = fn(Vec<Rc<Value>>) -> Result<Rc<Value>, InterpreterError>;
+/// The type of a built-in function. The `Interpreter` is passed so that built-in functions can
+/// call functions that are passed to them.
+pub type BuiltinFunction =
+    fn(&mut Interpreter, Vec<Rc<Value>>) -> Result<Rc<Value>, InterpreterError>;
 
 /// The representation of a value in `tini`.
 #[derive(Clone, Debug)]
 pub enum Value {
     /// An integer.
-    Integer(i64),
-    /// A function.
+    Integer(Integer),
+    /// A float.
+    Float(f64),
+    /// A string.
+    String(String),
+    /// A boolean.
+    Bool(bool),
+    /// A symbol, which is how an identifier or a keyword is represented in code that is data,
+    /// like the arguments of a macro.
+    Symbol(Identifier),
+    /// A function, together with the environment it was defined in.
     Function {
+        /// The name the function was defined with, if it was defined by a `define` or `defmacro`
+        /// expression.
+        name: Option<Identifier>,
         arguments: Vec<Identifier>,
         value: AST,
+        env: Environment,
     },
     /// A built-in function.
     Builtin(BuiltinFunction),
+    /// A pair of two values, also called a cons cell. A list is a chain of pairs, whose first
+    /// values are the elements of the list, ending with `Nil`.
+    Pair(Rc<Value>, Rc<Value>),
+    /// The empty list.
+    Nil,
+    /// A mutable box that contains a value.
+    Box(RefCell<Rc<Value>>),
 }
 
 impl Value {
@@ -28,8 +53,52 @@ impl Value {
 
         match self {
             Integer(_) => "int",
+            Float(_) => "float",
+            String(_) => "string",
+            Bool(_) => "bool",
+            Symbol(_) => "symbol",
             Function { .. } => "function",
             Builtin(_) => "function",
+            Pair(_, _) => "pair",
+            Nil => "nil",
+            Box(_) => "box",
+        }
+    }
+
+    /// Returns whether the value is true when it is used as a condition, like in an `if`
+    /// expression: `#f` and `0` are false, and every other value is true.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Bool(v) => *v,
+            Value::Integer(v) => !integer::is_zero(v),
+            _ => true,
+        }
+    }
+
+    /// Create a list of `elements`.
+    pub fn list(elements: Vec<Rc<Value>>) -> Rc<Value> {
+        elements
+            .into_iter()
+            .rev()
+            .fold(Rc::new(Value::Nil), |list, element| {
+                Rc::new(Value::Pair(element, list))
+            })
+    }
+
+    /// Return the elements of a list, or `None` if the value is not a list. A chain of pairs
+    /// that does not end with `Nil` is not a list.
+    pub fn list_elements(&self) -> Option<Vec<Rc<Value>>> {
+        let mut elements = Vec::new();
+        let mut value = self;
+        loop {
+            match value {
+                Value::Pair(car, cdr) => {
+                    elements.push(car.clone());
+                    value = cdr;
+                }
+                Value::Nil => return Some(elements),
+                _ => return None,
+            }
         }
     }
 }
@@ -40,8 +109,63 @@ impl fmt::Display for Value {
 
         match self {
             Integer(v) => write!(f, "{}", v),
+            // The `Debug` representation always contains a '.' or an exponent, so that floats
+            // can be told apart from integers.
+            Float(v) => write!(f, "{:?}", v),
+            String(s) => write!(f, "{}", s),
+            Bool(true) => write!(f, "#t"),
+            Bool(false) => write!(f, "#f"),
+            Symbol(name) => write!(f, "{}", name),
             Function { .. } => write!(f, "<function>"),
             Builtin(_) => write!(f, "<function>"),
+            // The contents are not printed, because a box can contain itself.
+            Box(_) => write!(f, "<box>"),
+            // Lists are printed like `(1 2 3)`; if the last pair does not end with `Nil`, its
+            // second value is printed after a '.', like `(1 2 . 3)`.
+            Pair(_, _) | Nil => {
+                write!(f, "(")?;
+                let mut value = self;
+                let mut separator = "";
+                while let Pair(car, cdr) = value {
+                    write!(f, "{}{}", separator, car)?;
+                    separator = " ";
+                    value = cdr;
+                }
+                if let Nil = value {
+                    write!(f, ")")
+                } else {
+                    write!(f, " . {})", value)
+                }
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// The empty list that the rest of a list is replaced with while the list is dropped.
+    static NIL: Rc<Value> = Rc::new(Value::Nil);
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        // Dropping a long list recursively could overflow the stack, so the rest of a list is
+        // dropped in a loop instead. Only a pair whose rest is a pair needs this.
+        let cdr = match self {
+            Value::Pair(_, cdr) if matches!(**cdr, Value::Pair(_, _)) => cdr,
+            _ => return,
+        };
+        // The rest is replaced with a shared empty list, so dropping does not allocate. When the
+        // thread is exiting and `NIL` is gone already, the list is dropped recursively.
+        let nil = match NIL.try_with(Rc::clone) {
+            Ok(nil) => nil,
+            Err(_) => return,
+        };
+        let mut rest = mem::replace(cdr, nil.clone());
+        while let Ok(mut value) = Rc::try_unwrap(rest) {
+            match &mut value {
+                Value::Pair(_, cdr) => rest = mem::replace(cdr, nil.clone()),
+                _ => break,
+            }
         }
     }
 }
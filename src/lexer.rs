@@ -5,6 +5,7 @@ mod error;
 pub use self::error::*;
 
 use self::error::LexerResult::*;
+use crate::integer;
 use crate::token::{Token, TokenType};
 use crate::Position;
 use std::iter::Peekable;
@@ -34,10 +35,10 @@ impl<'i> Lexer<'i> {
         let position = self.position;
 
         let peek_char = match self.peek_char() {
-            Some(ch) => ch,
+            Some(ch) => *ch,
             None => return Eof,
         };
-        let token = match *peek_char {
+        let token = match peek_char {
             '(' => {
                 self.read_char();
                 TokenType::LeftBracket
@@ -46,14 +47,43 @@ impl<'i> Lexer<'i> {
                 self.read_char();
                 TokenType::RightBracket
             }
+            '\'' => {
+                self.read_char();
+                TokenType::QuoteMark
+            }
+            '`' => {
+                self.read_char();
+                TokenType::Backquote
+            }
+            ',' => {
+                self.read_char();
+                TokenType::Comma
+            }
             ';' => {
                 self.read_comment();
                 return self.next_token();
             }
+            '"' => match self.read_string() {
+                Ok(t) => t,
+                Err(e) => return Err(e),
+                Eof => return Eof,
+            },
+            // A sign immediately followed by a digit begins a number, not an identifier.
+            '-' | '+' if self.peek_nth(1).map(|ch| ch.is_ascii_digit()) == Some(true) => {
+                match self.read_number(position) {
+                    Ok(t) => t,
+                    Err(e) => return Err(e),
+                    Eof => return Eof,
+                }
+            }
             ch if Lexer::is_identifier_begin(ch) => {
                 TokenType::identifier_or_keyword(self.read_identifier())
             }
-            ch if ch.is_digit(10) => self.read_number(),
+            ch if ch.is_ascii_digit() => match self.read_number(position) {
+                Ok(t) => t,
+                Err(e) => return Err(e),
+                Eof => return Eof,
+            },
             ch => {
                 return Err(LexerError::UnexpectedCharacter {
                     ch,
@@ -83,6 +113,11 @@ impl<'i> Lexer<'i> {
         self.input.peek()
     }
 
+    /// Peek the char `n` chars after the next char, so `peek_nth(0)` peeks the next char.
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.input.clone().nth(n)
+    }
+
     /// Read a comment, discarding it. A comment begins with ';', and ends with a newline.
     fn read_comment(&mut self) {
         while self.read_char().map(|ch| ch != '\n') == Some(true) {
@@ -90,6 +125,68 @@ impl<'i> Lexer<'i> {
         }
     }
 
+    /// Read a string literal and collect it into a `TokenType::String`, replacing the escape
+    /// sequences with the characters they represent.
+    /// This function must only be called when the next char in the input is '"'.
+    fn read_string(&mut self) -> LexerResult<TokenType> {
+        let mut string = String::new();
+
+        self.read_char();
+
+        loop {
+            match self.read_char() {
+                Some('"') => return Ok(TokenType::String(string)),
+                Some('\\') => match self.read_escape() {
+                    Ok(ch) => string.push(ch),
+                    Err(e) => return Err(e),
+                    Eof => return Eof,
+                },
+                Some(ch) => string.push(ch),
+                None => return Err(LexerError::UnexpectedEof { expected: "`\"`" }),
+            }
+        }
+    }
+
+    /// Read an escape sequence in a string literal, after the '\', and return the character it
+    /// represents. The supported escape sequences are `\n`, `\t`, `\\`, `\"` and `\u{...}`,
+    /// where `...` is the hexadecimal code point of a Unicode character.
+    fn read_escape(&mut self) -> LexerResult<char> {
+        let position = self.position;
+
+        match self.read_char() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('\\') => Ok('\\'),
+            Some('"') => Ok('"'),
+            Some('u') => {
+                let invalid = LexerError::UnknownEscape { ch: 'u', position };
+
+                if self.read_char() != Some('{') {
+                    return Err(invalid);
+                }
+                let mut digits = String::new();
+                while self.peek_char().map(|ch| ch.is_ascii_hexdigit()) == Some(true) {
+                    digits.push(self.read_char().unwrap());
+                }
+                if self.read_char() != Some('}') {
+                    return Err(invalid);
+                }
+
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(ch) => Ok(ch),
+                    None => Err(invalid),
+                }
+            }
+            Some(ch) => Err(LexerError::UnknownEscape { ch, position }),
+            None => Err(LexerError::UnexpectedEof {
+                expected: "escape character",
+            }),
+        }
+    }
+
     /// Read an identifier and collect it into a `String`.
     /// This function must only be called when the next char in the input is an identifier beginner
     /// (see `Lexer::is_identifier_begin`).
@@ -105,19 +202,96 @@ impl<'i> Lexer<'i> {
         ident
     }
 
-    /// Read a number and collect it into a `TokenType`. If a '.' is found after reading the first
-    /// digits, a `TokenType::Float` will be returned, otherwise a `TokenType::Integer`.
-    /// This function must only be called when the next char in the input is a digit.
-    fn read_number(&mut self) -> TokenType {
-        let mut number = String::new();
+    /// Read a number and collect it into a `TokenType`.
+    ///
+    /// A number is an integer or a float, optionally preceded by a '-' or '+'. An integer is
+    /// decimal, or hexadecimal, binary or octal when prefixed with `0x`, `0b` or `0o`. A float
+    /// is decimal and has a fraction (like `1.5`), an exponent (like `1e10`, `1E+10` or `1e-10`),
+    /// or both. Digits may be separated by '_', like in `1_000_000`.
+    ///
+    /// The number continues up to the next char that cannot be part of an identifier, so `12ab`
+    /// is a `LexerError::InvalidNumber`, and not `12` followed by `ab`.
+    /// This function must only be called when the next char in the input is a digit, or a '-' or
+    /// '+' followed by a digit, which is at `position`.
+    fn read_number(&mut self, position: Position) -> LexerResult<TokenType> {
+        let mut literal = String::new();
 
-        number.push(self.read_char().unwrap());
+        literal.push(self.read_char().unwrap());
 
-        while self.peek_char().map(|ch| ch.is_digit(10)) == Some(true) {
-            number.push(self.read_char().unwrap());
+        while self.peek_char().map(|ch| Lexer::is_identifier(*ch)) == Some(true) {
+            literal.push(self.read_char().unwrap());
+        }
+
+        let (sign, unsigned) = match literal.chars().next() {
+            Some(ch @ '-') | Some(ch @ '+') => (Some(ch), &literal[1..]),
+            _ => (None, &literal[..]),
+        };
+        let (radix, digits) = match unsigned.get(..2) {
+            Some("0x") | Some("0X") => (16, &unsigned[2..]),
+            Some("0b") | Some("0B") => (2, &unsigned[2..]),
+            Some("0o") | Some("0O") => (8, &unsigned[2..]),
+            _ => (10, unsigned),
+        };
+        let digits = match Lexer::remove_separators(digits, radix) {
+            Some(digits) => digits,
+            None => return Err(LexerError::InvalidNumber { literal, position }),
+        };
+        let number = sign.into_iter().chain(digits.chars()).collect::<String>();
+
+        if radix == 10 && Lexer::is_float(&digits) {
+            // A float literal always parses; if it is too large, it becomes infinity.
+            return Ok(TokenType::Float(number.parse().unwrap()));
+        }
+
+        if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+            return Err(LexerError::InvalidNumber { literal, position });
+        }
+
+        match integer::from_str_radix(&number, radix) {
+            Some(v) => Ok(TokenType::Integer(v)),
+            // The digits are valid, so the literal can only fail to parse if it is too large.
+            None => Err(LexerError::IntegerOverflow { literal, position }),
         }
+    }
+
+    /// Remove the '_'s that separate the digits of a number in base `radix`. Returns `None` if a
+    /// '_' does not separate two digits, like in `_1`, `1_` or `1__0`.
+    fn remove_separators(digits: &str, radix: u32) -> Option<String> {
+        let chars = digits.chars().collect::<Vec<char>>();
+        let is_digit = |i: Option<usize>| {
+            i.and_then(|i| chars.get(i)).map(|ch| ch.is_digit(radix)) == Some(true)
+        };
+
+        let mut result = String::with_capacity(digits.len());
+        for (i, ch) in chars.iter().enumerate() {
+            if *ch != '_' {
+                result.push(*ch);
+            } else if !is_digit(i.checked_sub(1)) || !is_digit(Some(i + 1)) {
+                return None;
+            }
+        }
+        Some(result)
+    }
+
+    /// Returns whether `digits`, an unsigned decimal number without separators, is a float:
+    /// digits followed by a fraction, an exponent, or both.
+    fn is_float(digits: &str) -> bool {
+        let is_digits = |s: &str| !s.is_empty() && s.chars().all(|ch| ch.is_ascii_digit());
+
+        let (mantissa, exponent) = match digits.find(['e', 'E']) {
+            Some(i) => (&digits[..i], Some(&digits[i + 1..])),
+            None => (digits, None),
+        };
+        let (integral, fraction) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], Some(&mantissa[i + 1..])),
+            None => (mantissa, None),
+        };
+        let exponent = exponent.map(|e| e.strip_prefix(['+', '-']).unwrap_or(e));
 
-        TokenType::Integer(number.parse().unwrap())
+        (fraction.is_some() || exponent.is_some())
+            && is_digits(integral)
+            && fraction.map_or(true, is_digits)
+            && exponent.map_or(true, is_digits)
     }
 
     /// Skip all whitespace characters.
@@ -129,12 +303,12 @@ impl<'i> Lexer<'i> {
 
     /// Returns whether `ch` can be the begin of an identifier.
     fn is_identifier_begin(ch: char) -> bool {
-        ch.is_alphabetic() || "'!@#$%^&*-=+|:/?,.<>`~_".contains(ch)
+        ch.is_alphabetic() || "!@#$%^&*-=+|:/?.<>~_".contains(ch)
     }
 
     /// Returns whether `ch` can be inside an identifier.
     fn is_identifier(ch: char) -> bool {
-        ch.is_alphabetic() || ch.is_digit(10) || "'!@#$%^&*-=+|:/?,.<>`~_".contains(ch)
+        ch.is_alphabetic() || ch.is_ascii_digit() || "!@#$%^&*-=+|:/?.<>~_".contains(ch)
     }
 }
 
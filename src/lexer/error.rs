@@ -16,26 +16,17 @@ pub enum LexerResult<T> {
 impl<T> LexerResult<T> {
     /// Returns `true` if the result is `Ok`.
     pub fn is_ok(&self) -> bool {
-        match self {
-            LexerResult::Ok(_) => true,
-            _ => false,
-        }
+        matches!(self, LexerResult::Ok(_))
     }
 
     /// Returns `true` if the result is `Err`.
     pub fn is_err(&self) -> bool {
-        match self {
-            LexerResult::Err(_) => true,
-            _ => false,
-        }
+        matches!(self, LexerResult::Err(_))
     }
 
     /// Returns `true` if the result is `Eof`.
     pub fn is_eof(&self) -> bool {
-        match self {
-            LexerResult::Eof => true,
-            _ => false,
-        }
+        matches!(self, LexerResult::Eof)
     }
 
     /// Unwrap an `Ok` value.
@@ -74,7 +65,21 @@ pub enum LexerError {
         /// The unknown escape character.
         ch: char,
         /// The position of the character.
-        position: Position
+        position: Position,
+    },
+    /// An integer literal is too large to be represented as an integer.
+    IntegerOverflow {
+        /// The integer literal.
+        literal: String,
+        /// The position of the literal.
+        position: Position,
+    },
+    /// A number literal is malformed, like `0x` or `12ab`.
+    InvalidNumber {
+        /// The number literal.
+        literal: String,
+        /// The position of the literal.
+        position: Position,
     },
     /// A different error.
     Other {
@@ -98,6 +103,16 @@ impl fmt::Display for LexerError {
             UnexpectedEof { expected } => {
                 write!(f, "unexpected end of file, expected {}", expected)
             }
+            IntegerOverflow { literal, position } => {
+                write!(
+                    f,
+                    "integer literal at {} is too large: {}",
+                    position, literal
+                )
+            }
+            InvalidNumber { literal, position } => {
+                write!(f, "invalid number literal at {}: {}", position, literal)
+            }
             Other { error, position } => write!(f, "error at {}: {}", position, error),
         }
     }
@@ -1,7 +1,7 @@
-#![feature(external_doc)]
-#![doc(include = "../README.md")]
+#![doc = include_str!("../README.md")]
 
 pub mod ast;
+pub mod integer;
 pub mod interpreter;
 pub mod lexer;
 pub mod parser;
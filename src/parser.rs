@@ -5,22 +5,34 @@ mod error;
 pub use self::error::*;
 
 use self::error::ParseResult::*;
-use crate::ast::{ASTType, AST};
-use crate::lexer::Lexer;
+use crate::ast::{ASTType, Datum, AST};
+use crate::lexer::{Lexer, LexerError};
 use crate::token::{Token, TokenType};
+use crate::{Identifier, Position};
 use std::iter::Peekable;
 
+/// The type of the stream of `Token`s that a `Parser` reads.
+type Tokens<'i> = Box<dyn Iterator<Item = Result<Token, LexerError>> + 'i>;
+
 /// The `Parser` turns a stream of `Token`s into `AST`s.
 pub struct Parser<'i> {
-    /// The `Lexer`, from which the `Token`s will be read.
-    lexer: Peekable<Lexer<'i>>,
+    /// The stream from which the `Token`s will be read, usually a `Lexer`.
+    lexer: Peekable<Tokens<'i>>,
 }
 
 impl<'i> Parser<'i> {
     /// Create a new `Parser` with a `Lexer` that supplies `Token`s.
     pub fn new(lexer: Lexer<'i>) -> Parser<'i> {
         Parser {
-            lexer: lexer.peekable(),
+            lexer: (Box::new(lexer) as Tokens<'i>).peekable(),
+        }
+    }
+
+    /// Create a new `Parser` that parses `tokens`, which do not come from a `Lexer`. This is how
+    /// code that is made by a macro is parsed.
+    pub fn from_tokens(tokens: Vec<Token>) -> Parser<'static> {
+        Parser {
+            lexer: (Box::new(tokens.into_iter().map(Result::Ok)) as Tokens).peekable(),
         }
     }
 
@@ -35,13 +47,54 @@ impl<'i> Parser<'i> {
         let position = token.position;
         let ast = match token.token {
             TokenType::Integer(v) => ASTType::Integer(v),
+            TokenType::Float(v) => ASTType::Float(v),
+            TokenType::String(s) => ASTType::String(s),
+            TokenType::Bool(v) => ASTType::Bool(v),
             TokenType::Identifier(ident) => ASTType::Identifier(ident),
             TokenType::LeftBracket => return self.parse_function(),
+            TokenType::QuoteMark => match self.parse_datum(None) {
+                Ok(datum) => ASTType::Quote(datum),
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "expression after `'`",
+                    })
+                }
+            },
+            TokenType::Backquote => match self.parse_datum(Some(1)) {
+                Ok(datum) => ASTType::Quasiquote(datum),
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "expression after `` ` ``",
+                    })
+                }
+            },
             // Unexpected tokens. Do not use `_` here, to cause compile errors when a new
             // `TokenType` is added.
-            TokenType::RightBracket | TokenType::Define | TokenType::If => {
-                return Err(ParseError::UnexpectedToken(token))
-            }
+            TokenType::RightBracket
+            | TokenType::Define
+            | TokenType::Defmacro
+            | TokenType::If
+            | TokenType::Lambda
+            | TokenType::And
+            | TokenType::Or
+            | TokenType::Not
+            | TokenType::Let
+            | TokenType::LetStar
+            | TokenType::Letrec
+            | TokenType::Begin
+            | TokenType::Cond
+            | TokenType::Case
+            | TokenType::Else
+            | TokenType::Set
+            | TokenType::Comma
+            | TokenType::Quote
+            | TokenType::Quasiquote
+            | TokenType::Unquote
+            | TokenType::Try
+            | TokenType::Catch
+            | TokenType::Import => return Err(ParseError::UnexpectedToken(token)),
         };
 
         Ok(AST { ast, position })
@@ -67,27 +120,41 @@ impl<'i> Parser<'i> {
     /// Parse a function call.
     fn parse_function(&mut self) -> ParseResult<AST> {
         match self.peek_token_type() {
-            Some(&TokenType::Identifier(_)) => self.parse_function_call(),
             Some(&TokenType::If) => self.parse_if(),
             Some(&TokenType::Define) => self.parse_define(),
-            None => Err(ParseError::UnexpectedEof {
-                expected: "`if`, `define`, a value, or an identifier",
-            }),
-            _ => Err(ParseError::UnexpectedToken(self.next_token().unwrap())),
+            Some(&TokenType::Defmacro) => self.parse_defmacro(),
+            Some(&TokenType::Set) => self.parse_set(),
+            Some(&TokenType::Lambda) => self.parse_lambda(),
+            Some(&TokenType::And) | Some(&TokenType::Or) => self.parse_and_or(),
+            Some(&TokenType::Not) => self.parse_not(),
+            Some(&TokenType::Begin) => self.parse_begin(),
+            Some(&TokenType::Cond) => self.parse_cond(),
+            Some(&TokenType::Case) => self.parse_case(),
+            Some(&TokenType::Quote) | Some(&TokenType::Quasiquote) => self.parse_quote(),
+            Some(&TokenType::Try) => self.parse_try(),
+            Some(&TokenType::Import) => self.parse_import(),
+            Some(&TokenType::Let) | Some(&TokenType::LetStar) | Some(&TokenType::Letrec) => {
+                self.parse_let()
+            }
+            Some(&TokenType::RightBracket) => {
+                Err(ParseError::UnexpectedToken(self.next_token().unwrap()))
+            }
+            _ => self.parse_function_call(),
         }
     }
 
-    /// Parse a function call expression.
+    /// Parse a function call expression. The function that is called can be any expression.
     fn parse_function_call(&mut self) -> ParseResult<AST> {
-        let name = self.next_token().unwrap();
-        let position = name.position;
-
-        let name = match name.token {
-            TokenType::Identifier(n) => n,
-            _ => panic!(
-                "parse_function_call may only be called when the next token is an identifier."
-            ),
+        let function = match self.parse_expression() {
+            Ok(f) => f,
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "a keyword, a value, or an identifier",
+                })
+            }
         };
+        let position = function.position;
 
         let mut arguments = Vec::new();
         while self.peek_token_type() != Some(&TokenType::RightBracket) {
@@ -112,7 +179,10 @@ impl<'i> Parser<'i> {
         }
 
         Ok(AST {
-            ast: ASTType::FunctionCall { name, arguments },
+            ast: ASTType::FunctionCall {
+                function: Box::new(function),
+                arguments,
+            },
             position,
         })
     }
@@ -183,9 +253,20 @@ impl<'i> Parser<'i> {
 
         let (name, arguments) = match self.parse_expression() {
             Ok(AST {
-                ast: ASTType::FunctionCall { name, arguments },
+                ast:
+                    ASTType::FunctionCall {
+                        function,
+                        arguments,
+                    },
                 ..
             }) => {
+                let name = match *function {
+                    AST {
+                        ast: ASTType::Identifier(name),
+                        ..
+                    } => name,
+                    expr => return Err(ParseError::UnexpectedExpression(expr)),
+                };
                 let mut identifiers = Vec::new();
                 for parameter in arguments {
                     match parameter {
@@ -206,6 +287,22 @@ impl<'i> Parser<'i> {
             e => return e,
         };
 
+        // A function body can consist of multiple expressions.
+        if arguments.is_some() {
+            let value = match self.parse_body("body of function definition") {
+                Ok(v) => v,
+                e => return e,
+            };
+            return Ok(AST {
+                ast: ASTType::Define {
+                    name,
+                    arguments,
+                    value: Box::new(value),
+                },
+                position,
+            });
+        }
+
         let value = match self.parse_expression() {
             Ok(v) => v,
             e => return e,
@@ -232,6 +329,896 @@ impl<'i> Parser<'i> {
             position,
         })
     }
+
+    /// Parse a defmacro expression.
+    fn parse_defmacro(&mut self) -> ParseResult<AST> {
+        let defmacro_token = self.next_token().unwrap();
+        assert_eq!(defmacro_token.token, TokenType::Defmacro);
+
+        let position = defmacro_token.position;
+
+        // Next token must be a left bracket.
+        let left_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Err(ParseError::UnexpectedEof { expected: "`(`" }),
+        };
+
+        match left_bracket.token {
+            TokenType::LeftBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(left_bracket)),
+        }
+
+        let name = match self.next_token() {
+            Ok(Token {
+                token: TokenType::Identifier(name),
+                ..
+            }) => name,
+            Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "name of macro",
+                })
+            }
+        };
+
+        let arguments = match self.parse_identifiers() {
+            Ok(a) => a,
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        let value = match self.parse_body("body of macro definition") {
+            Ok(v) => v,
+            e => return e,
+        };
+
+        Ok(AST {
+            ast: ASTType::Defmacro {
+                name,
+                arguments,
+                value: Box::new(value),
+            },
+            position,
+        })
+    }
+
+    /// Parse an import expression. A file can be given by its path, as a string, or by its name,
+    /// as an identifier: `lib` is the same as `"lib.tini"`.
+    fn parse_import(&mut self) -> ParseResult<AST> {
+        let import_token = self.next_token().unwrap();
+        assert_eq!(import_token.token, TokenType::Import);
+
+        let position = import_token.position;
+
+        let path = match self.next_token() {
+            Ok(Token {
+                token: TokenType::String(path),
+                ..
+            }) => path,
+            Ok(Token {
+                token: TokenType::Identifier(name),
+                ..
+            }) => format!("{}.tini", name),
+            Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "file in import expression",
+                })
+            }
+        };
+
+        // The names are given like `(only f g)`. `only` is not a keyword, so it can still be used
+        // as a variable.
+        let names = match self.next_token() {
+            Ok(Token {
+                token: TokenType::RightBracket,
+                ..
+            }) => None,
+            Ok(Token {
+                token: TokenType::LeftBracket,
+                ..
+            }) => {
+                match self.next_token() {
+                    Ok(Token {
+                        token: TokenType::Identifier(ref only),
+                        ..
+                    }) if only == "only" => {}
+                    Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+                    Err(e) => return Err(e),
+                    Eof => return Err(ParseError::UnexpectedEof { expected: "`only`" }),
+                }
+                let names = match self.parse_identifiers() {
+                    Ok(n) => n,
+                    Err(e) => return Err(e),
+                    Eof => return Eof,
+                };
+
+                // Next token must be a right bracket.
+                let right_bracket = match self.next_token() {
+                    Ok(t) => t,
+                    Err(e) => return Err(e),
+                    Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+                };
+
+                match right_bracket.token {
+                    TokenType::RightBracket => {}
+                    _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+                }
+
+                Some(names)
+            }
+            Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "`(only ...)` or `)`",
+                })
+            }
+        };
+
+        Ok(AST {
+            ast: ASTType::Import { path, names },
+            position,
+        })
+    }
+
+    /// Parse a set! expression.
+    fn parse_set(&mut self) -> ParseResult<AST> {
+        let set_token = self.next_token().unwrap();
+        assert_eq!(set_token.token, TokenType::Set);
+
+        let position = set_token.position;
+
+        let name = match self.next_token() {
+            Ok(Token {
+                token: TokenType::Identifier(name),
+                ..
+            }) => name,
+            Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "variable in set! expression",
+                })
+            }
+        };
+
+        let value = match self.parse_expression() {
+            Ok(v) => Box::new(v),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "value in set! expression",
+                })
+            }
+        };
+
+        // Next token must be a right bracket.
+        let right_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+        };
+
+        match right_bracket.token {
+            TokenType::RightBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+        }
+
+        Ok(AST {
+            ast: ASTType::Set { name, value },
+            position,
+        })
+    }
+
+    /// Parse a lambda expression.
+    fn parse_lambda(&mut self) -> ParseResult<AST> {
+        let lambda_token = self.next_token().unwrap();
+        assert_eq!(lambda_token.token, TokenType::Lambda);
+
+        let position = lambda_token.position;
+
+        let arguments = match self.parse_parameters() {
+            Ok(a) => a,
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "parameter list in lambda expression",
+                })
+            }
+        };
+
+        let value = match self.parse_body("body of lambda expression") {
+            Ok(v) => v,
+            e => return e,
+        };
+
+        Ok(AST {
+            ast: ASTType::Lambda {
+                arguments,
+                value: Box::new(value),
+            },
+            position,
+        })
+    }
+
+    /// Parse an `and` or an `or` expression.
+    fn parse_and_or(&mut self) -> ParseResult<AST> {
+        let keyword_token = self.next_token().unwrap();
+        let position = keyword_token.position;
+
+        let mut operands = Vec::new();
+        while self.peek_token_type() != Some(&TokenType::RightBracket) {
+            match self.parse_expression() {
+                Ok(v) => operands.push(v),
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "operand or `)`",
+                    })
+                }
+            }
+        }
+        self.next_token();
+
+        let ast = match keyword_token.token {
+            TokenType::And => ASTType::And(operands),
+            TokenType::Or => ASTType::Or(operands),
+            _ => unreachable!("`parse_and_or` must only be called on `and` or `or`"),
+        };
+        Ok(AST { ast, position })
+    }
+
+    /// Parse a not expression.
+    fn parse_not(&mut self) -> ParseResult<AST> {
+        let not_token = self.next_token().unwrap();
+        assert_eq!(not_token.token, TokenType::Not);
+
+        let position = not_token.position;
+
+        let operand = match self.parse_expression() {
+            Ok(v) => Box::new(v),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "operand of not expression",
+                })
+            }
+        };
+
+        // Next token must be a right bracket.
+        let right_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+        };
+
+        match right_bracket.token {
+            TokenType::RightBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+        }
+
+        Ok(AST {
+            ast: ASTType::Not(operand),
+            position,
+        })
+    }
+
+    /// Parse a `let`, `let*` or `letrec` expression.
+    fn parse_let(&mut self) -> ParseResult<AST> {
+        let let_token = self.next_token().unwrap();
+        let position = let_token.position;
+
+        let bindings = match self.parse_bindings() {
+            Ok(b) => b,
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "binding list in let expression",
+                })
+            }
+        };
+
+        let value = match self.parse_body("body of let expression") {
+            Ok(v) => Box::new(v),
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        let ast = match let_token.token {
+            TokenType::Let => ASTType::Let { bindings, value },
+            TokenType::LetStar => ASTType::LetStar { bindings, value },
+            TokenType::Letrec => ASTType::Letrec { bindings, value },
+            _ => unreachable!("`parse_let` must only be called on `let`, `let*` or `letrec`"),
+        };
+        Ok(AST { ast, position })
+    }
+
+    /// Parse a cond expression.
+    fn parse_cond(&mut self) -> ParseResult<AST> {
+        let cond_token = self.next_token().unwrap();
+        assert_eq!(cond_token.token, TokenType::Cond);
+
+        let position = cond_token.position;
+
+        let mut clauses = Vec::new();
+        let mut alternative = None;
+        loop {
+            let clause_position = match self.parse_clause_start(alternative.is_some()) {
+                Ok(Some(p)) => p,
+                Ok(None) => break,
+                Err(e) => return Err(e),
+                Eof => return Eof,
+            };
+
+            if self.peek_token_type() == Some(&TokenType::Else) {
+                self.next_token();
+                match self.parse_clause_body(clause_position) {
+                    Ok(v) => alternative = Some(Box::new(v)),
+                    e => return e,
+                }
+                continue;
+            }
+
+            if self.peek_token_type() == Some(&TokenType::RightBracket) {
+                return Err(ParseError::MalformedClause {
+                    expected: "a condition",
+                    position: clause_position,
+                });
+            }
+            let condition = match self.parse_expression() {
+                Ok(v) => v,
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "condition",
+                    })
+                }
+            };
+            match self.parse_clause_body(clause_position) {
+                Ok(v) => clauses.push((condition, v)),
+                e => return e,
+            }
+        }
+
+        Ok(AST {
+            ast: ASTType::Cond {
+                clauses,
+                alternative,
+            },
+            position,
+        })
+    }
+
+    /// Parse a case expression.
+    fn parse_case(&mut self) -> ParseResult<AST> {
+        let case_token = self.next_token().unwrap();
+        assert_eq!(case_token.token, TokenType::Case);
+
+        let position = case_token.position;
+
+        let key = match self.parse_expression() {
+            Ok(v) => Box::new(v),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "key in case expression",
+                })
+            }
+        };
+
+        let mut clauses = Vec::new();
+        let mut alternative = None;
+        loop {
+            let clause_position = match self.parse_clause_start(alternative.is_some()) {
+                Ok(Some(p)) => p,
+                Ok(None) => break,
+                Err(e) => return Err(e),
+                Eof => return Eof,
+            };
+
+            if self.peek_token_type() == Some(&TokenType::Else) {
+                self.next_token();
+                match self.parse_clause_body(clause_position) {
+                    Ok(v) => alternative = Some(Box::new(v)),
+                    e => return e,
+                }
+                continue;
+            }
+
+            // The values of a clause are a list of integer literals, like `(1 2 3)`.
+            let malformed = ParseError::MalformedClause {
+                expected: "a list of integer literals, or `else`",
+                position: clause_position,
+            };
+            match self.next_token() {
+                Ok(Token {
+                    token: TokenType::LeftBracket,
+                    ..
+                }) => {}
+                Ok(_) => return Err(malformed),
+                Err(e) => return Err(e),
+                Eof => return Err(ParseError::UnexpectedEof { expected: "`(`" }),
+            }
+            let mut values = Vec::new();
+            loop {
+                match self.next_token() {
+                    Ok(Token {
+                        token: TokenType::Integer(v),
+                        ..
+                    }) => values.push(v),
+                    Ok(Token {
+                        token: TokenType::RightBracket,
+                        ..
+                    }) => break,
+                    Ok(_) => return Err(malformed),
+                    Err(e) => return Err(e),
+                    Eof => {
+                        return Err(ParseError::UnexpectedEof {
+                            expected: "integer literal or `)`",
+                        })
+                    }
+                }
+            }
+
+            match self.parse_clause_body(clause_position) {
+                Ok(v) => clauses.push((values, v)),
+                e => return e,
+            }
+        }
+
+        Ok(AST {
+            ast: ASTType::Case {
+                key,
+                clauses,
+                alternative,
+            },
+            position,
+        })
+    }
+
+    /// Parse the `(` that starts a clause of a cond or case expression, and return its position.
+    /// Returns `None` if the expression ends instead. `after_else` is whether an `else` clause
+    /// was already parsed, which must be the last clause.
+    fn parse_clause_start(&mut self, after_else: bool) -> ParseResult<Option<Position>> {
+        let token = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "clause or `)`",
+                })
+            }
+        };
+
+        match token.token {
+            TokenType::RightBracket => Ok(None),
+            TokenType::LeftBracket if after_else => Err(ParseError::MalformedClause {
+                expected: "no clause after the `else` clause",
+                position: token.position,
+            }),
+            TokenType::LeftBracket => Ok(Some(token.position)),
+            _ => Err(ParseError::MalformedClause {
+                expected: "a clause between brackets",
+                position: token.position,
+            }),
+        }
+    }
+
+    /// Parse the expressions of a clause of a cond or case expression, which is at `position`,
+    /// and the `)` that ends the clause.
+    fn parse_clause_body(&mut self, position: Position) -> ParseResult<AST> {
+        if self.peek_token_type() == Some(&TokenType::RightBracket) {
+            return Err(ParseError::MalformedClause {
+                expected: "an expression",
+                position,
+            });
+        }
+        self.parse_body("expression in clause")
+    }
+
+    /// Parse a try expression.
+    fn parse_try(&mut self) -> ParseResult<AST> {
+        let try_token = self.next_token().unwrap();
+        assert_eq!(try_token.token, TokenType::Try);
+
+        let position = try_token.position;
+
+        let value = match self.parse_expression() {
+            Ok(v) => Box::new(v),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "expression in try expression",
+                })
+            }
+        };
+
+        // The catch clause is written like `(catch e handler ...)`.
+        for expected in &[TokenType::LeftBracket, TokenType::Catch] {
+            match self.next_token() {
+                Ok(ref t) if t.token == *expected => {}
+                Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "catch clause",
+                    })
+                }
+            }
+        }
+
+        let name = match self.next_token() {
+            Ok(Token {
+                token: TokenType::Identifier(name),
+                ..
+            }) => name,
+            Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "variable in catch clause",
+                })
+            }
+        };
+
+        let handler = match self.parse_body("expression in catch clause") {
+            Ok(v) => Box::new(v),
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        // Next token must be a right bracket.
+        let right_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+        };
+
+        match right_bracket.token {
+            TokenType::RightBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+        }
+
+        Ok(AST {
+            ast: ASTType::Try {
+                value,
+                name,
+                handler,
+            },
+            position,
+        })
+    }
+
+    /// Parse a `quote` or a `quasiquote` expression.
+    fn parse_quote(&mut self) -> ParseResult<AST> {
+        let quote_token = self.next_token().unwrap();
+        let position = quote_token.position;
+
+        let level = match quote_token.token {
+            TokenType::Quote => None,
+            TokenType::Quasiquote => Some(1),
+            _ => unreachable!("`parse_quote` must only be called on `quote` or `quasiquote`"),
+        };
+        let datum = match self.parse_datum(level) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "operand of quote expression",
+                })
+            }
+        };
+
+        // Next token must be a right bracket.
+        let right_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+        };
+
+        match right_bracket.token {
+            TokenType::RightBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+        }
+
+        let ast = match level {
+            None => ASTType::Quote(datum),
+            Some(_) => ASTType::Quasiquote(datum),
+        };
+        Ok(AST { ast, position })
+    }
+
+    /// Parse a datum: code that is data, like the operand of a `quote` expression. Any expression
+    /// can be a datum, and so can a keyword or `()`. `'x` is read as `(quote x)`, `` `x `` as
+    /// `(quasiquote x)` and `,x` as `(unquote x)`.
+    ///
+    /// `level` is the number of `quasiquote`s the datum is in, or `None` if it is in a `quote`.
+    /// An `unquote` ends a `quasiquote`, so at level 1, the operand of an `unquote` is parsed as an
+    /// expression and becomes `Datum::Unquote`.
+    fn parse_datum(&mut self, level: Option<usize>) -> ParseResult<Datum> {
+        let token = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        let datum = match token.token {
+            TokenType::Integer(v) => Datum::Integer(v),
+            TokenType::Float(v) => Datum::Float(v),
+            TokenType::String(s) => Datum::String(s),
+            TokenType::Bool(v) => Datum::Bool(v),
+            TokenType::Identifier(name) => Datum::Symbol(name),
+            TokenType::QuoteMark => return self.parse_quoted_datum(TokenType::Quote, level),
+            TokenType::Backquote => return self.parse_quoted_datum(TokenType::Quasiquote, level),
+            TokenType::Comma => return self.parse_quoted_datum(TokenType::Unquote, level),
+            TokenType::LeftBracket => {
+                // The long forms of `quasiquote` and `unquote` change the level like the short
+                // forms.
+                match self.peek_token_type() {
+                    Some(&TokenType::Quasiquote) | Some(&TokenType::Unquote) if level.is_some() => {
+                        let keyword = self.next_token().unwrap().token;
+                        let datum = match self.parse_quoted_datum(keyword, level) {
+                            Ok(d) => d,
+                            e => return e,
+                        };
+
+                        // Next token must be a right bracket.
+                        let right_bracket = match self.next_token() {
+                            Ok(t) => t,
+                            Err(e) => return Err(e),
+                            Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+                        };
+
+                        match right_bracket.token {
+                            TokenType::RightBracket => {}
+                            _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+                        }
+
+                        return Ok(datum);
+                    }
+                    _ => {}
+                }
+
+                let mut elements = Vec::new();
+                while self.peek_token_type() != Some(&TokenType::RightBracket) {
+                    match self.parse_datum(level) {
+                        Ok(d) => elements.push(d),
+                        Err(e) => return Err(e),
+                        Eof => {
+                            return Err(ParseError::UnexpectedEof {
+                                expected: "datum or `)`",
+                            })
+                        }
+                    }
+                }
+                self.next_token();
+                Datum::List(elements)
+            }
+            TokenType::RightBracket => return Err(ParseError::UnexpectedToken(token)),
+            // Every keyword is a symbol.
+            keyword => Datum::Symbol(keyword.to_string()),
+        };
+
+        Ok(datum)
+    }
+
+    /// Parse the operand of `keyword`, which is `quote`, `quasiquote` or `unquote`, in a datum
+    /// at `level`; see `Parser::parse_datum`.
+    fn parse_quoted_datum(
+        &mut self,
+        keyword: TokenType,
+        level: Option<usize>,
+    ) -> ParseResult<Datum> {
+        let level = match (&keyword, level) {
+            (TokenType::Unquote, Some(1)) => {
+                return match self.parse_expression() {
+                    Ok(v) => Ok(Datum::Unquote(Box::new(v))),
+                    Err(e) => Err(e),
+                    Eof => Err(ParseError::UnexpectedEof {
+                        expected: "operand of unquote",
+                    }),
+                }
+            }
+            (TokenType::Unquote, level) => level.map(|level| level - 1),
+            (TokenType::Quasiquote, level) => level.map(|level| level + 1),
+            (_, level) => level,
+        };
+
+        let operand = match self.parse_datum(level) {
+            Ok(d) => d,
+            Err(e) => return Err(e),
+            Eof => {
+                return Err(ParseError::UnexpectedEof {
+                    expected: "operand of quote expression",
+                })
+            }
+        };
+        Ok(Datum::List(vec![
+            Datum::Symbol(keyword.to_string()),
+            operand,
+        ]))
+    }
+
+    /// Parse a begin expression.
+    fn parse_begin(&mut self) -> ParseResult<AST> {
+        let begin_token = self.next_token().unwrap();
+        assert_eq!(begin_token.token, TokenType::Begin);
+
+        let position = begin_token.position;
+
+        let expressions = match self.parse_sequence("expression in begin expression") {
+            Ok(e) => e,
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        Ok(AST {
+            ast: ASTType::Begin(expressions),
+            position,
+        })
+    }
+
+    /// Parse the body of a function or a let expression: one or more expressions, followed by a
+    /// `)`. If there is more than one expression, they are put in a begin expression. `expected`
+    /// describes the body, for when the end of the file is found before it.
+    fn parse_body(&mut self, expected: &'static str) -> ParseResult<AST> {
+        let mut expressions = match self.parse_sequence(expected) {
+            Ok(e) => e,
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        if expressions.len() == 1 {
+            return Ok(expressions.pop().unwrap());
+        }
+        let position = expressions[0].position;
+        Ok(AST {
+            ast: ASTType::Begin(expressions),
+            position,
+        })
+    }
+
+    /// Parse one or more expressions, followed by a `)`. `expected` describes the first
+    /// expression, for when the end of the file is found before it.
+    fn parse_sequence(&mut self, expected: &'static str) -> ParseResult<Vec<AST>> {
+        let mut expressions = Vec::new();
+        loop {
+            match self.parse_expression() {
+                Ok(v) => expressions.push(v),
+                Err(e) => return Err(e),
+                Eof if expressions.is_empty() => {
+                    return Err(ParseError::UnexpectedEof { expected })
+                }
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "expression or `)`",
+                    })
+                }
+            }
+
+            if self.peek_token_type() == Some(&TokenType::RightBracket) {
+                self.next_token();
+                return Ok(expressions);
+            }
+        }
+    }
+
+    /// Parse a binding list, in the form `((x 1) (y 2))`.
+    fn parse_bindings(&mut self) -> ParseResult<Vec<(Identifier, AST)>> {
+        let left_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        match left_bracket.token {
+            TokenType::LeftBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(left_bracket)),
+        }
+
+        let mut bindings = Vec::new();
+        loop {
+            let token = match self.next_token() {
+                Ok(t) => t,
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "binding or `)`",
+                    })
+                }
+            };
+
+            match token.token {
+                TokenType::LeftBracket => {}
+                TokenType::RightBracket => break,
+                _ => return Err(ParseError::UnexpectedToken(token)),
+            }
+
+            let name = match self.next_token() {
+                Ok(Token {
+                    token: TokenType::Identifier(name),
+                    ..
+                }) => name,
+                Ok(t) => return Err(ParseError::UnexpectedToken(t)),
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "variable in binding",
+                    })
+                }
+            };
+
+            let value = match self.parse_expression() {
+                Ok(v) => v,
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "value in binding",
+                    })
+                }
+            };
+
+            // Next token must be a right bracket.
+            let right_bracket = match self.next_token() {
+                Ok(t) => t,
+                Err(e) => return Err(e),
+                Eof => return Err(ParseError::UnexpectedEof { expected: "`)`" }),
+            };
+
+            match right_bracket.token {
+                TokenType::RightBracket => {}
+                _ => return Err(ParseError::UnexpectedToken(right_bracket)),
+            }
+
+            bindings.push((name, value));
+        }
+
+        Ok(bindings)
+    }
+
+    /// Parse a parameter list, in the form `(x y z)`.
+    fn parse_parameters(&mut self) -> ParseResult<Vec<Identifier>> {
+        let left_bracket = match self.next_token() {
+            Ok(t) => t,
+            Err(e) => return Err(e),
+            Eof => return Eof,
+        };
+
+        match left_bracket.token {
+            TokenType::LeftBracket => {}
+            _ => return Err(ParseError::UnexpectedToken(left_bracket)),
+        }
+
+        self.parse_identifiers()
+    }
+
+    /// Parse identifiers up to the next `)`, like the parameters in a parameter list.
+    fn parse_identifiers(&mut self) -> ParseResult<Vec<Identifier>> {
+        let mut parameters = Vec::new();
+        loop {
+            let token = match self.next_token() {
+                Ok(t) => t,
+                Err(e) => return Err(e),
+                Eof => {
+                    return Err(ParseError::UnexpectedEof {
+                        expected: "parameter or `)`",
+                    })
+                }
+            };
+
+            match token.token {
+                TokenType::Identifier(p) => parameters.push(p),
+                TokenType::RightBracket => break,
+                _ => return Err(ParseError::UnexpectedToken(token)),
+            }
+        }
+
+        Ok(parameters)
+    }
 }
 
 impl<'i> Iterator for Parser<'i> {
@@ -19,26 +19,17 @@ pub enum ParseResult<T> {
 impl<T> ParseResult<T> {
     /// Returns `true` if the result is `Ok`.
     pub fn is_ok(&self) -> bool {
-        match self {
-            ParseResult::Ok(_) => true,
-            _ => false,
-        }
+        matches!(self, ParseResult::Ok(_))
     }
 
     /// Returns `true` if the result is `Err`.
     pub fn is_err(&self) -> bool {
-        match self {
-            ParseResult::Err(_) => true,
-            _ => false,
-        }
+        matches!(self, ParseResult::Err(_))
     }
 
     /// Returns `true` if the result is `Eof`.
     pub fn is_eof(&self) -> bool {
-        match self {
-            ParseResult::Eof => true,
-            _ => false,
-        }
+        matches!(self, ParseResult::Eof)
     }
 
     /// Unwrap an `Ok` value.
@@ -69,6 +60,13 @@ pub enum ParseError {
     UnexpectedExpression(AST),
     /// End of file found, but expected token.
     UnexpectedEof { expected: &'static str },
+    /// A clause of a `cond` or `case` expression is malformed.
+    MalformedClause {
+        /// What the clause was expected to look like.
+        expected: &'static str,
+        /// The position of the clause.
+        position: Position,
+    },
     /// An error happened in the lexer.
     LexerError(LexerError),
     /// A different error.
@@ -91,6 +89,9 @@ impl fmt::Display for ParseError {
                 write!(f, "unexpected expression at {}: {:?}", position, ast)
             }
             UnexpectedEof { expected } => write!(f, "found end of file, but expected {}", expected),
+            MalformedClause { expected, position } => {
+                write!(f, "malformed clause at {}: expected {}", position, expected)
+            }
             LexerError(e) => write!(f, "{}", e),
             Other { error, position } => write!(f, "error at {}: {}", position, error),
         }
@@ -1,5 +1,6 @@
 //! `token` contains the `Token` structure.
 
+use crate::integer::Integer;
 use crate::{Identifier, Position};
 use std::fmt;
 
@@ -28,16 +29,66 @@ pub enum TokenType {
     /// An identifier.
     Identifier(Identifier),
     /// An integer literal.
-    Integer(i64),
+    Integer(Integer),
+    /// A float literal.
+    Float(f64),
+    /// A string literal, with its escape sequences replaced.
+    String(String),
+    /// A boolean literal: `#t` or `#f`.
+    Bool(bool),
     /// `(`
     LeftBracket,
     /// `)`
     RightBracket,
+    /// `'`, which quotes the next expression.
+    QuoteMark,
+    /// `` ` ``, which quasiquotes the next expression.
+    Backquote,
+    /// `,`, which unquotes the next expression in a quasiquoted expression.
+    Comma,
 
     /// `if`
     If,
     /// `define`
     Define,
+    /// `defmacro`
+    Defmacro,
+    /// `lambda`
+    Lambda,
+    /// `and`
+    And,
+    /// `or`
+    Or,
+    /// `not`
+    Not,
+    /// `let`
+    Let,
+    /// `let*`
+    LetStar,
+    /// `letrec`
+    Letrec,
+    /// `begin`
+    Begin,
+    /// `cond`
+    Cond,
+    /// `case`
+    Case,
+    /// `else`
+    Else,
+    /// `set!`
+    Set,
+    /// `quote`
+    Quote,
+    /// `quasiquote`
+    Quasiquote,
+    /// `unquote`
+    Unquote,
+    /// `try`
+    Try,
+    /// `catch`
+    Catch,
+    /// `import`
+    Import,
 }
 
 impl TokenType {
@@ -47,6 +98,27 @@ impl TokenType {
         match name.as_str() {
             "if" => TokenType::If,
             "define" => TokenType::Define,
+            "defmacro" => TokenType::Defmacro,
+            "lambda" => TokenType::Lambda,
+            "and" => TokenType::And,
+            "or" => TokenType::Or,
+            "not" => TokenType::Not,
+            "let" => TokenType::Let,
+            "let*" => TokenType::LetStar,
+            "letrec" => TokenType::Letrec,
+            "begin" => TokenType::Begin,
+            "cond" => TokenType::Cond,
+            "case" => TokenType::Case,
+            "else" => TokenType::Else,
+            "set!" => TokenType::Set,
+            "quote" => TokenType::Quote,
+            "quasiquote" => TokenType::Quasiquote,
+            "unquote" => TokenType::Unquote,
+            "try" => TokenType::Try,
+            "catch" => TokenType::Catch,
+            "import" => TokenType::Import,
+            "#t" => TokenType::Bool(true),
+            "#f" => TokenType::Bool(false),
             _ => TokenType::Identifier(name),
         }
     }
@@ -58,10 +130,36 @@ impl fmt::Display for TokenType {
         match self {
             Identifier(ident) => write!(f, "{}", ident),
             Integer(v) => write!(f, "{}", v),
+            Float(v) => write!(f, "{:?}", v),
+            String(s) => write!(f, "{:?}", s),
+            Bool(true) => write!(f, "#t"),
+            Bool(false) => write!(f, "#f"),
             LeftBracket => write!(f, "("),
             RightBracket => write!(f, ")"),
+            QuoteMark => write!(f, "'"),
+            Backquote => write!(f, "`"),
+            Comma => write!(f, ","),
             If => write!(f, "if"),
             Define => write!(f, "define"),
+            Defmacro => write!(f, "defmacro"),
+            Lambda => write!(f, "lambda"),
+            And => write!(f, "and"),
+            Or => write!(f, "or"),
+            Not => write!(f, "not"),
+            Let => write!(f, "let"),
+            LetStar => write!(f, "let*"),
+            Letrec => write!(f, "letrec"),
+            Begin => write!(f, "begin"),
+            Cond => write!(f, "cond"),
+            Case => write!(f, "case"),
+            Else => write!(f, "else"),
+            Set => write!(f, "set!"),
+            Quote => write!(f, "quote"),
+            Quasiquote => write!(f, "quasiquote"),
+            Unquote => write!(f, "unquote"),
+            Try => write!(f, "try"),
+            Catch => write!(f, "catch"),
+            Import => write!(f, "import"),
         }
     }
 }
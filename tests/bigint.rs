@@ -0,0 +1,76 @@
+#![cfg(feature = "bigint")]
+
+use tini::integer::BigInt;
+use tini::prelude::*;
+
+/// Evaluate every expression in `input` and return the last value as a string.
+fn run(input: &str) -> String {
+    let mut interpreter = Interpreter::new();
+    let mut value = String::new();
+    for expr in Parser::new(Lexer::new(input)) {
+        value = interpreter.eval(expr.unwrap()).unwrap().to_string();
+    }
+    value
+}
+
+#[test]
+fn bigint_parse_and_display() {
+    for input in &[
+        "0",
+        "1",
+        "-1",
+        "4294967295",
+        "4294967296",
+        "-18446744073709551616",
+        "123456789012345678901234567890",
+    ] {
+        assert_eq!(input.parse::<BigInt>().unwrap().to_string(), *input);
+    }
+    assert_eq!("-0".parse::<BigInt>().unwrap(), BigInt::zero());
+    assert!("12a".parse::<BigInt>().is_err());
+    assert!("-".parse::<BigInt>().is_err());
+}
+
+#[test]
+fn bigint_arithmetic() {
+    let big: BigInt = "18446744073709551616".parse().unwrap();
+    let one = BigInt::from(1);
+
+    assert_eq!((&big - &one).to_string(), "18446744073709551615");
+    assert_eq!((&one - &big).to_string(), "-18446744073709551615");
+    assert_eq!((&big + &-&big), BigInt::zero());
+    assert_eq!(
+        (&big * &-&big).to_string(),
+        "-340282366920938463463374607431768211456"
+    );
+    assert_eq!(BigInt::from(i64::MIN).to_string(), i64::MIN.to_string());
+    for &v in &[0, -1, i64::MAX, i64::MIN, i64::MIN + 1] {
+        assert_eq!(BigInt::from(v).to_i64(), Some(v));
+    }
+    assert_eq!((-&big).to_i64(), None);
+    assert_eq!((&BigInt::from(i64::MIN) - &one).to_i64(), None);
+    assert!(-&big < one && one < big);
+}
+
+#[test]
+fn factorial_does_not_overflow() {
+    let input = r#"
+    (define (fac n)
+        (if (= n 1)
+            1
+            (* n (fac (- n 1)))))
+    (fac 30)
+    "#;
+
+    assert_eq!(run(input), "265252859812191058636308480000000");
+}
+
+#[test]
+fn large_literals() {
+    assert_eq!(
+        run("(+ 9223372036854775807 9223372036854775807)"),
+        "18446744073709551614"
+    );
+    assert_eq!(run("(> 9223372036854775808 9223372036854775807)"), "#t");
+    assert_eq!(run("(= 99999999999999999999 99999999999999999999)"), "#t");
+}
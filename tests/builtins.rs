@@ -0,0 +1,331 @@
+use tini::interpreter::InterpreterError;
+use tini::prelude::*;
+
+/// Evaluate `input` in a new `Interpreter` and return the value of the last expression as a
+/// string.
+fn run(input: &str) -> Result<String, InterpreterError> {
+    let mut interpreter = Interpreter::new();
+    let mut value = String::new();
+    for expr in Parser::new(Lexer::new(input)) {
+        value = interpreter.eval(expr.unwrap())?.to_string();
+    }
+    Ok(value)
+}
+
+/// Assert that every input evaluates to the expected output.
+fn assert_outputs(cases: &[(&str, &str)]) {
+    for (input, output) in cases {
+        assert_eq!(run(input).unwrap(), *output, "input: {}", input);
+    }
+}
+
+#[test]
+fn division_and_remainder() {
+    assert_outputs(&[
+        ("(/ 7 2)", "3"),
+        ("(/ (- 0 7) 2)", "-3"),
+        ("(rem 7 2)", "1"),
+        ("(rem (- 0 7) 2)", "-1"),
+        ("(rem 7 (- 0 2))", "1"),
+        ("(mod 7 2)", "1"),
+        ("(mod (- 0 7) 2)", "1"),
+        ("(mod 7 (- 0 2))", "-1"),
+        ("(mod 6 (- 0 2))", "0"),
+    ]);
+}
+
+#[test]
+fn division_by_zero() {
+    for name in &["/", "mod", "rem"] {
+        match run(&format!("({} 1 0)", name)) {
+            Err(InterpreterError::DivisionByZero { name: n }) => assert_eq!(n, *name),
+            v => panic!("expected division by zero error, found {:?}", v),
+        }
+    }
+}
+
+#[test]
+fn integer_math() {
+    assert_outputs(&[
+        ("(abs (- 0 5))", "5"),
+        ("(abs 5)", "5"),
+        ("(min 3 (- 0 4))", "-4"),
+        ("(max 3 (- 0 4))", "3"),
+        ("(pow 2 10)", "1024"),
+        ("(pow (- 0 3) 3)", "-27"),
+        ("(pow 5 0)", "1"),
+        ("(gcd 12 18)", "6"),
+        ("(gcd (- 0 12) 18)", "6"),
+        ("(gcd 0 0)", "0"),
+    ]);
+}
+
+#[test]
+fn negative_exponent() {
+    match run("(pow 2 (- 0 1))") {
+        Err(e @ InterpreterError::BuiltinValueError { .. }) => assert_eq!(
+            e.to_string(),
+            "built-in function pow got an invalid argument: integer exponent -1 is negative"
+        ),
+        v => panic!("expected value error, found {:?}", v),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn integer_math_overflow() {
+    for (input, name) in &[
+        ("(/ (- (- 0 9223372036854775807) 1) (- 0 1))", "/"),
+        ("(abs (- (- 0 9223372036854775807) 1))", "abs"),
+        ("(pow 2 63)", "pow"),
+    ] {
+        match run(input) {
+            Err(InterpreterError::ArithmeticOverflow { name: n, .. }) => assert_eq!(n, *name),
+            v => panic!("expected arithmetic overflow error, found {:?}", v),
+        }
+    }
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn string_to_int_overflow() {
+    for input in &["99999999999999999999", "-99999999999999999999"] {
+        match run(&format!("(string->int {:?})", input)) {
+            Err(InterpreterError::BuiltinValueError { name, description }) => {
+                assert_eq!(name, "string->int");
+                assert_eq!(
+                    description,
+                    format!("{:?} is too large for an integer", input)
+                );
+            }
+            v => panic!("expected value error, found {:?}", v),
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "bigint")]
+fn bigint_math() {
+    assert_outputs(&[
+        ("(pow 2 100)", "1267650600228229401496703205376"),
+        ("(/ (pow 2 100) (pow 3 30))", "6156897518274294"),
+        ("(rem (pow 2 100) (pow 3 30))", "130128751552570"),
+        ("(mod (- 0 (pow 2 100)) (pow 3 30))", "75762380542079"),
+        ("(gcd (pow 6 40) (pow 4 30))", "1099511627776"),
+        (
+            r#"(string->int "99999999999999999999")"#,
+            "99999999999999999999",
+        ),
+    ]);
+}
+
+#[test]
+fn variadic_arithmetic() {
+    assert_outputs(&[
+        ("(+)", "0"),
+        ("(+ 5)", "5"),
+        ("(+ 1 2 3 4)", "10"),
+        ("(*)", "1"),
+        ("(* 5)", "5"),
+        ("(* 1 2 3 4)", "24"),
+        ("(- 5)", "-5"),
+        ("(- 10 1 2 3)", "4"),
+        ("(- -10 +1 -2)", "-9"),
+    ]);
+
+    match run("(-)") {
+        Err(InterpreterError::BuiltinArgumentError { name, got, .. }) => {
+            assert_eq!((name, got), ("-", 0))
+        }
+        v => panic!("expected argument error, found {:?}", v),
+    }
+    match run("(+ 1 2 (lambda () 0))") {
+        Err(InterpreterError::BuiltinTypeError { name, found, .. }) => {
+            assert_eq!((name, found), ("+", "function"))
+        }
+        v => panic!("expected type error, found {:?}", v),
+    }
+}
+
+#[test]
+fn variadic_comparison() {
+    assert_outputs(&[
+        ("(<)", "#t"),
+        ("(< 1)", "#t"),
+        ("(< 1 2 3)", "#t"),
+        ("(< 1 3 2)", "#f"),
+        ("(< 1 1 2)", "#f"),
+        ("(> 3 2 1)", "#t"),
+        ("(> 3 1 2)", "#f"),
+        ("(= 2 2 2)", "#t"),
+        ("(= 2 2 3)", "#f"),
+        ("(= 1 (lambda () 1))", "#f"),
+        ("(= #t #t)", "#t"),
+        ("(= #t #f)", "#f"),
+        (r#"(= "a" "a" "a")"#, "#t"),
+        (r#"(= "a" "b")"#, "#f"),
+        ("(= 'a 'a)", "#t"),
+        ("(= nil nil)", "#t"),
+        ("(= nil #f)", "#f"),
+        (r#"(= 1 "1")"#, "#f"),
+        ("(= (list 1) (list 1))", "#f"),
+        ("(= (lambda () 1))", "#f"),
+        ("(< (lambda () 1))", "#f"),
+    ]);
+}
+
+#[test]
+fn string_builtins() {
+    assert_outputs(&[
+        (r#""hello""#, "hello"),
+        (r#"(string-length "héllo")"#, "5"),
+        (r#"(concat "foo" "bar" "baz")"#, "foobarbaz"),
+        ("(concat)", ""),
+        (r#"(substring "héllo" 1 3)"#, "él"),
+        (r#"(substring "hello" 5 5)"#, ""),
+        (r#"(+ (string->int "-42") 2)"#, "-40"),
+        ("(concat (int->string 42) \"!\")", "42!"),
+    ]);
+}
+
+#[test]
+fn invalid_string_arguments() {
+    for (input, name) in &[
+        (r#"(substring "hello" 2 6)"#, "substring"),
+        (r#"(substring "hello" 3 2)"#, "substring"),
+        (r#"(substring "hello" (- 0 1) 2)"#, "substring"),
+        (r#"(string->int "12a")"#, "string->int"),
+    ] {
+        match run(input) {
+            Err(InterpreterError::BuiltinValueError { name: n, .. }) => assert_eq!(n, *name),
+            v => panic!("expected value error, found {:?}", v),
+        }
+    }
+
+    match run("(string-length 5)") {
+        Err(InterpreterError::BuiltinTypeError { found, .. }) => assert_eq!(found, "int"),
+        v => panic!("expected type error, found {:?}", v),
+    }
+}
+
+#[test]
+fn float_arithmetic() {
+    assert_outputs(&[
+        ("1.5", "1.5"),
+        ("(+ 1 2.5)", "3.5"),
+        ("(- 1.5)", "-1.5"),
+        ("(- 10 2.5 0.5)", "7.0"),
+        ("(* 2 1.5)", "3.0"),
+        ("(/ 7 2.0)", "3.5"),
+        ("(/ 1.0 0)", "inf"),
+        ("(/ (- 1.0) 0)", "-inf"),
+        ("(/ 0.0 0)", "NaN"),
+        ("(rem (- 0 7.5) 2)", "-1.5"),
+        ("(mod (- 0 7.5) 2)", "0.5"),
+        ("(abs (- 0 2.5))", "2.5"),
+        ("(min 1 2.5)", "1.0"),
+        ("(max 1 2.5)", "2.5"),
+        ("(max (/ 0.0 0) 1)", "NaN"),
+        ("(pow 2 0.5)", "1.4142135623730951"),
+        ("(pow 2.0 (- 0 1))", "0.5"),
+    ]);
+}
+
+#[test]
+fn float_comparison() {
+    assert_outputs(&[
+        ("(= 1 1.0)", "#t"),
+        ("(< 1 1.5 2)", "#t"),
+        ("(> 2.5 2)", "#t"),
+        ("(= (/ 0.0 0) (/ 0.0 0))", "#f"),
+        ("(< (/ 0.0 0) 1)", "#f"),
+        ("(> (/ 0.0 0) 1)", "#f"),
+        ("(< 1 (/ 1.0 0))", "#t"),
+    ]);
+
+    match run(r#"(+ 1.5 "a")"#) {
+        Err(InterpreterError::BuiltinTypeError { expected, .. }) => assert_eq!(expected, "number"),
+        v => panic!("expected type error, found {:?}", v),
+    }
+}
+
+#[test]
+fn lists() {
+    assert_outputs(&[
+        ("nil", "()"),
+        ("(list)", "()"),
+        ("(cons 1 2)", "(1 . 2)"),
+        ("(cons 1 (cons 2 nil))", "(1 2)"),
+        ("(list 1 (list 2 3) \"four\" 5.0)", "(1 (2 3) four 5.0)"),
+        ("(cons 1 (cons 2 3))", "(1 2 . 3)"),
+        ("(car (list 1 2 3))", "1"),
+        ("(cdr (list 1 2 3))", "(2 3)"),
+        ("(null? nil)", "#t"),
+        ("(null? (list 1))", "#f"),
+        ("(length (list 1 2 3))", "3"),
+        ("(length nil)", "0"),
+        ("(append (list 1 2) nil (list 3) (list 4 5))", "(1 2 3 4 5)"),
+        ("(append)", "()"),
+        ("(reverse (list 1 2 3))", "(3 2 1)"),
+        ("(map (lambda (x) (* x x)) (list 1 2 3))", "(1 4 9)"),
+        ("(map abs (list -1 2 -3))", "(1 2 3)"),
+        ("(filter (lambda (x) (> x 1)) (list 1 2 3))", "(2 3)"),
+        ("(fold + 0 (list 1 2 3))", "6"),
+        (
+            "(fold (lambda (acc x) (cons x acc)) nil (list 1 2 3))",
+            "(3 2 1)",
+        ),
+    ]);
+}
+
+#[test]
+fn invalid_list_arguments() {
+    for (input, name) in &[
+        ("(car nil)", "car"),
+        ("(cdr 1)", "cdr"),
+        ("(length (cons 1 2))", "length"),
+        ("(append (list 1) 2)", "append"),
+        ("(map abs 1)", "map"),
+    ] {
+        match run(input) {
+            Err(InterpreterError::BuiltinTypeError { name: n, .. }) => assert_eq!(n, *name),
+            v => panic!("expected type error, found {:?}", v),
+        }
+    }
+
+    match run("(map (lambda (x y) x) (list 1 2))") {
+        Err(InterpreterError::ArgumentError { takes, got, .. }) => assert_eq!((takes, got), (2, 1)),
+        v => panic!("expected argument error, found {:?}", v),
+    }
+    match run("(map 1 (list 1 2))") {
+        Err(InterpreterError::TypeError { found, .. }) => assert_eq!(found, "int"),
+        v => panic!("expected type error, found {:?}", v),
+    }
+}
+
+#[test]
+fn boxes() {
+    assert_outputs(&[
+        ("(box 1)", "<box>"),
+        ("(unbox (box 1))", "1"),
+        (
+            "(define b (box 1)) (set-box! b (+ (unbox b) 1)) (unbox b)",
+            "2",
+        ),
+        (
+            r#"
+            (define b (box nil))
+            (define (push x) (set-box! b (cons x (unbox b))))
+            (push 1) (push 2) (unbox b)
+            "#,
+            "(2 1)",
+        ),
+    ]);
+
+    for (input, name) in &[("(unbox 1)", "unbox"), ("(set-box! nil 1)", "set-box!")] {
+        match run(input) {
+            Err(InterpreterError::BuiltinTypeError { name: n, .. }) => assert_eq!(n, *name),
+            v => panic!("expected type error, found {:?}", v),
+        }
+    }
+}
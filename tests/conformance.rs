@@ -0,0 +1,98 @@
+//! Run the examples in the README and in `examples/`, and check that they print what their
+//! documentation says they print.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Run `tinii` on the file `path`, and return what it printed.
+fn run_file(path: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_tinii"))
+        .arg(path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{} failed: {}",
+        path.display(),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Run the tini code `input` with `tinii`, and return what it printed.
+fn run(name: &str, input: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, input).unwrap();
+    run_file(&path)
+}
+
+/// Get the code blocks in a Markdown document that begin with "```" followed by `language`.
+fn code_blocks(markdown: &str, language: &str) -> Vec<(usize, String)> {
+    let mut blocks = Vec::new();
+    let mut lines = markdown.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        if line.trim() != format!("```{}", language) {
+            continue;
+        }
+        let mut block = String::new();
+        for (_, line) in lines.by_ref().take_while(|(_, line)| line.trim() != "```") {
+            block.push_str(line);
+            block.push('\n');
+        }
+        blocks.push((i + 1, block));
+    }
+    blocks
+}
+
+#[test]
+fn readme_examples() {
+    let readme = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/README.md")).unwrap();
+
+    let mut examples = 0;
+    for (line, code) in code_blocks(&readme, "tini") {
+        // The output of an example is the first `plain` code block after it.
+        let rest = readme.lines().skip(line).collect::<Vec<_>>().join("\n");
+        let output = match code_blocks(&rest, "plain").into_iter().next() {
+            Some((_, output)) => output,
+            None => panic!("the example at README.md:{} has no output", line),
+        };
+        assert_eq!(
+            run(&format!("readme-{}.tini", line), &code),
+            output,
+            "the example at README.md:{} printed something else",
+            line
+        );
+        examples += 1;
+    }
+    assert!(examples > 0, "no examples found in README.md");
+}
+
+#[test]
+fn example_files() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+
+    let mut examples = 0;
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) != Some("tini") {
+            continue;
+        }
+
+        // Every line that is printed is documented with a `; Prints ...` comment.
+        let expected = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.split("; Prints ").nth(1))
+            .map(|output| format!("{}\n", output.trim()))
+            .collect::<String>();
+        assert_eq!(
+            run_file(&path),
+            expected,
+            "{} printed something else",
+            path.display()
+        );
+        examples += 1;
+    }
+    assert!(examples > 0, "no examples found in examples/");
+}
@@ -0,0 +1,1153 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::thread;
+use tini::integer::Integer;
+use tini::interpreter::{
+    quote, unquote, InterpreterError, DEFAULT_MAX_DEPTH, STACK_SIZE, STACK_SIZE_MAX_DEPTH,
+};
+use tini::prelude::*;
+
+/// Evaluate every expression in `input` and return the value of the last one.
+fn run(interpreter: &mut Interpreter, input: &str) -> Result<Rc<Value>, InterpreterError> {
+    let mut value = Rc::new(Value::Integer(0.into()));
+    for expr in Parser::new(Lexer::new(input)) {
+        value = interpreter.eval(expr.unwrap())?;
+    }
+    Ok(value)
+}
+
+/// Evaluate `input` in a new `Interpreter` and return the value of the last expression, which
+/// must be an integer.
+fn run_integer(input: &str) -> Integer {
+    match &*run(&mut Interpreter::new(), input).unwrap() {
+        Value::Integer(v) => v.to_owned(),
+        v => panic!("expected integer, found {}", v),
+    }
+}
+
+#[test]
+fn closures_are_lexically_scoped() {
+    let input = r#"
+    (define x 1)
+    (define (get-x) x)
+    (define (f x) (get-x))
+    (f 2)
+    "#;
+
+    assert_eq!(run_integer(input), 1);
+}
+
+#[test]
+fn closures_capture_their_defining_environment() {
+    let input = r#"
+    (define (make-adder n)
+        (define (adder x) (+ x n))
+        adder)
+    (define add-three (make-adder 3))
+    (define n 100)
+    (add-three 4)
+    "#;
+
+    assert_eq!(run_integer(input), 7);
+}
+
+#[test]
+fn function_definitions_do_not_leak_parameters() {
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, "(define (f x) x) (f 1)").unwrap();
+
+    match run(&mut interpreter, "x") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "x"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+}
+
+#[test]
+fn lambda_creates_anonymous_function() {
+    let input = r#"
+    (define (twice f x) (f (f x)))
+    (twice (lambda (x) (* x 3)) 2)
+    "#;
+
+    assert_eq!(run_integer(input), 18);
+}
+
+#[test]
+fn lambda_captures_environment() {
+    let input = r#"
+    (define (make-adder n) (lambda (x) (+ x n)))
+    (define add-three (make-adder 3))
+    (add-three 4)
+    "#;
+
+    assert_eq!(run_integer(input), 7);
+}
+
+#[test]
+fn call_result_of_expression() {
+    let input = r#"
+    (define (make-adder n) (lambda (x) (+ x n)))
+    ((make-adder 3) 4)
+    "#;
+
+    assert_eq!(run_integer(input), 7);
+    assert_eq!(run_integer("((lambda (x y) (- x y)) 5 2)"), 3);
+}
+
+#[test]
+fn call_non_function() {
+    match run(&mut Interpreter::new(), "(1 2)") {
+        Err(InterpreterError::TypeError { found, .. }) => assert_eq!(found, "int"),
+        v => panic!("expected type error, found {:?}", v),
+    }
+}
+
+#[test]
+fn failing_call_body_restores_environment() {
+    let mut interpreter = Interpreter::new();
+    run(
+        &mut interpreter,
+        "(define x 1) (define (f x) (undefined x))",
+    )
+    .unwrap();
+
+    assert!(run(&mut interpreter, "(f 2)").is_err());
+    match &*run(&mut interpreter, "x").unwrap() {
+        Value::Integer(v) => assert_eq!(*v, 1),
+        v => panic!("expected integer, found {}", v),
+    }
+}
+
+#[test]
+fn failing_call_argument_restores_environment() {
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, "(define x 1) (define (f x y) x)").unwrap();
+
+    assert!(run(&mut interpreter, "(f 2 (undefined))").is_err());
+    match &*run(&mut interpreter, "x").unwrap() {
+        Value::Integer(v) => assert_eq!(*v, 1),
+        v => panic!("expected integer, found {}", v),
+    }
+}
+
+#[test]
+fn failing_call_does_not_leave_parameters_bound() {
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, "(define (f a b) (f a))").unwrap();
+
+    assert!(run(&mut interpreter, "(f 1 2)").is_err());
+    for name in &["a", "b"] {
+        match run(&mut interpreter, name) {
+            Err(InterpreterError::UnknownVariable { .. }) => {}
+            v => panic!("expected unknown variable error, found {:?}", v),
+        }
+    }
+}
+
+#[test]
+fn tail_calls_do_not_overflow_stack() {
+    let input = r#"
+    (define (count n acc)
+        (if (= n 0)
+            acc
+            (count (- n 1) (+ acc 1))))
+    (count 100000 0)
+    "#;
+
+    assert_eq!(run_integer(input), 100000);
+}
+
+#[test]
+fn tail_calls_through_lambda() {
+    let input = r#"
+    (define (loop f n) (if (= n 0) 0 (f f (- n 1))))
+    (loop (lambda (self n) (loop self n)) 100000)
+    "#;
+
+    assert_eq!(run_integer(input), 0);
+}
+
+#[test]
+fn deep_recursion_returns_stack_overflow() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_depth(100);
+    let input = r#"
+    (define (sum n) (if (= n 0) 0 (+ n (sum (- n 1)))))
+    (sum 1000)
+    "#;
+
+    match run(&mut interpreter, input) {
+        Err(InterpreterError::StackOverflow {
+            depth, backtrace, ..
+        }) => {
+            assert_eq!(depth, 100);
+            // Every call to `sum` is one level deeper than the previous one, but the innermost
+            // levels are the arguments of calls that are not made yet.
+            assert!(backtrace.len() > 90 && backtrace.len() < 100);
+            for call in backtrace {
+                assert_eq!(call.name, Some("sum".to_string()));
+            }
+        }
+        v => panic!("expected stack overflow error, found {:?}", v),
+    }
+
+    // The interpreter can still be used after a stack overflow.
+    assert_eq!(run(&mut interpreter, "(sum 10)").unwrap().to_string(), "55");
+}
+
+/// A function that recurses without a base case that is ever reached.
+const SUM: &str = "(define (sum n) (if (= n 0) 0 (+ n (sum (- n 1))))) (sum 100000)";
+
+/// Run `input`, which must recurse forever, on a thread with a stack of `stack_size` bytes, with a
+/// maximum depth of `max_depth`, and return the depth of the stack overflow.
+fn stack_overflow_depth(stack_size: usize, max_depth: usize, input: String) -> usize {
+    thread::Builder::new()
+        .stack_size(stack_size)
+        .spawn(move || {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_max_depth(max_depth);
+            match run(&mut interpreter, &input) {
+                Err(InterpreterError::StackOverflow { depth, .. }) => depth,
+                v => panic!("expected stack overflow error, found {:?}", v),
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+/// The stack size of a main thread on most platforms.
+const MAIN_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+#[test]
+fn default_max_depth_fits_in_main_thread() {
+    assert_eq!(
+        stack_overflow_depth(MAIN_THREAD_STACK_SIZE, DEFAULT_MAX_DEPTH, SUM.to_string()),
+        DEFAULT_MAX_DEPTH
+    );
+    assert_eq!(Interpreter::new().max_depth(), DEFAULT_MAX_DEPTH);
+}
+
+#[test]
+fn stack_size_max_depth_fits_in_stack_size() {
+    assert_eq!(
+        stack_overflow_depth(STACK_SIZE, STACK_SIZE_MAX_DEPTH, SUM.to_string()),
+        STACK_SIZE_MAX_DEPTH
+    );
+}
+
+#[test]
+fn recursion_inside_expressions_returns_stack_overflow() {
+    // Every kind of expression that evaluates an expression inside it, with `x` the expression.
+    let shapes = [
+        "(+ 0 x)",
+        "(let ((y x)) y)",
+        "(let* ((y x)) y)",
+        "(letrec ((y x)) y)",
+        "(if x 1 0)",
+        "(cond (x 1) (else 0))",
+        "(case x ((1) 1) (else 0))",
+        "(begin x 1)",
+        "(and x 1)",
+        "(or (not x) 1)",
+        "(begin (set! n x) n)",
+        "(begin (define y x) y)",
+        "((lambda (y) y) x)",
+        "(car `(,x))",
+        "(car (map (lambda (y) x) (list 0)))",
+        "(car (filter (lambda (y) x) (list 0)))",
+        "(fold (lambda (y z) x) 0 (list 0))",
+    ];
+    for shape in &shapes {
+        // The recursive call is nested 20 times in the expression, so it is far deeper than the
+        // number of calls.
+        let mut body = "(f (- n 1))".to_string();
+        for _ in 0..20 {
+            body = shape.replace('x', &body);
+        }
+        let input = format!("(define (f n) {}) (f 100000)", body);
+
+        for &(stack_size, max_depth) in &[
+            (MAIN_THREAD_STACK_SIZE, DEFAULT_MAX_DEPTH),
+            (STACK_SIZE, STACK_SIZE_MAX_DEPTH),
+        ] {
+            assert_eq!(
+                stack_overflow_depth(stack_size, max_depth, input.clone()),
+                max_depth,
+                "{}",
+                shape
+            );
+        }
+    }
+}
+
+#[test]
+fn tail_calls_do_not_increase_depth() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_depth(10);
+    let input = r#"
+    (define (count n) (if (= n 0) 0 (count (- n 1))))
+    (count 1000)
+    "#;
+
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "0");
+}
+
+#[test]
+fn recursion_through_builtins_returns_stack_overflow() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_depth(100);
+    let input = r#"
+    (define (nest n) (if (= n 0) nil (map nest (list (- n 1)))))
+    (nest 1000)
+    "#;
+
+    match run(&mut interpreter, input) {
+        Err(InterpreterError::StackOverflow { backtrace, .. }) => {
+            assert_eq!(backtrace[0].name, Some("map".to_string()));
+            // A function that is called by a built-in function has the name it was defined with.
+            assert_eq!(backtrace[1].name, Some("nest".to_string()));
+        }
+        v => panic!("expected stack overflow error, found {:?}", v),
+    }
+}
+
+#[test]
+fn long_lists_do_not_overflow_stack() {
+    let input = r#"
+    (define (build n acc) (if (= n 0) acc (build (- n 1) (cons n acc))))
+    (length (build 200000 nil))
+    "#;
+
+    assert_eq!(run_integer(input), Integer::from(200000));
+}
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn arithmetic_overflow() {
+    for (input, name) in &[
+        ("(+ 9223372036854775807 1)", "+"),
+        ("(- (- 0 9223372036854775807) 2)", "-"),
+        ("(* 4611686018427387904 2)", "*"),
+    ] {
+        match run(&mut Interpreter::new(), input) {
+            Err(InterpreterError::ArithmeticOverflow { name: n, .. }) => assert_eq!(n, *name),
+            v => panic!("expected arithmetic overflow error, found {:?}", v),
+        }
+    }
+
+    // The error reports the arguments, not the partial result that overflowed.
+    match run(&mut Interpreter::new(), "(+ 9223372036854775800 5 5)") {
+        Err(InterpreterError::ArithmeticOverflow { operands, .. }) => {
+            assert_eq!(operands, [9223372036854775800, 5, 5])
+        }
+        v => panic!("expected arithmetic overflow error, found {:?}", v),
+    }
+}
+
+#[test]
+fn booleans() {
+    let mut interpreter = Interpreter::new();
+    for (input, output) in &[
+        ("#t", "#t"),
+        ("(if #f 1 2)", "2"),
+        ("(if (< 1 2) 1 2)", "1"),
+        ("(not #f)", "#t"),
+        ("(not 0)", "#t"),
+        ("(not (lambda () 0))", "#f"),
+        ("(and)", "#t"),
+        ("(or)", "#f"),
+        ("(and #t 5)", "5"),
+        ("(and 1 #f 5)", "#f"),
+        ("(or #f 0 5)", "5"),
+        ("(or #f 1 5)", "1"),
+        ("(or #f #f)", "#f"),
+    ] {
+        let value = run(&mut interpreter, input).unwrap();
+        assert_eq!(value.to_string(), *output, "input: {}", input);
+    }
+}
+
+#[test]
+fn and_or_short_circuit() {
+    let mut interpreter = Interpreter::new();
+    // `undefined` is not bound, so evaluating it would be an error.
+    assert_eq!(
+        run(&mut interpreter, "(and #f undefined)")
+            .unwrap()
+            .to_string(),
+        "#f"
+    );
+    assert_eq!(
+        run(&mut interpreter, "(or 1 undefined)")
+            .unwrap()
+            .to_string(),
+        "1"
+    );
+    match run(&mut interpreter, "(and #t undefined)") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "undefined"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+}
+
+#[test]
+fn strict_mode_requires_boolean_conditions() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_strict(true);
+    assert!(interpreter.is_strict());
+
+    assert_eq!(
+        run(&mut interpreter, "(if (= 1 1) 1 2)")
+            .unwrap()
+            .to_string(),
+        "1"
+    );
+    for (input, found) in &[
+        ("(if 0 1 2)", "int"),
+        ("(if (lambda () #f) 1 2)", "function"),
+        ("(and 1 #t)", "int"),
+        ("(or nil #t)", "nil"),
+        ("(not 0)", "int"),
+        ("(filter (lambda (x) x) (list 1))", "int"),
+    ] {
+        match run(&mut interpreter, input) {
+            Err(InterpreterError::TypeError { found: f, .. }) => assert_eq!(f, *found),
+            v => panic!("expected type error for {}, found {:?}", input, v),
+        }
+    }
+}
+
+#[test]
+fn truthiness() {
+    assert!(Value::Bool(true).is_truthy());
+    assert!(!Value::Bool(false).is_truthy());
+    assert!(!Value::Integer(0.into()).is_truthy());
+    assert!(Value::Integer(1.into()).is_truthy());
+    assert!(Value::String(String::new()).is_truthy());
+    assert!(Value::Nil.is_truthy());
+}
+
+#[test]
+fn let_forms() {
+    for (input, output) in &[
+        ("(let ((x 1) (y 2)) (+ x y))", "3"),
+        ("(let () 5)", "5"),
+        ("(let ((x 1)) (let ((x 2) (y x)) y))", "1"),
+        ("(let* ((x 1) (y (+ x 1))) (* x y))", "2"),
+        ("(let ((x 1)) (let* ((x 2) (y x)) y))", "2"),
+        ("(let* ((x 1) (f (lambda () x)) (x 2)) (f))", "1"),
+        (
+            r#"(letrec ((even? (lambda (n) (if (= n 0) #t (odd? (- n 1)))))
+                        (odd? (lambda (n) (if (= n 0) #f (even? (- n 1))))))
+                 (even? 100001))"#,
+            "#f",
+        ),
+    ] {
+        let value = run(&mut Interpreter::new(), input).unwrap();
+        assert_eq!(value.to_string(), *output, "input: {}", input);
+    }
+}
+
+#[test]
+fn let_bindings_do_not_leak() {
+    let mut interpreter = Interpreter::new();
+    for input in &[
+        "(let ((x 1)) x)",
+        "(let* ((x 1)) x)",
+        "(letrec ((x 1)) x)",
+        "(let () (define x 1))",
+        "(let* () (define x 1))",
+    ] {
+        run(&mut interpreter, input).unwrap();
+        match run(&mut interpreter, "x") {
+            Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "x"),
+            v => panic!(
+                "expected unknown variable error after {}, found {:?}",
+                input, v
+            ),
+        }
+    }
+
+    // `let` values cannot refer to the variables of the same `let`.
+    match run(&mut interpreter, "(let ((x 1) (y x)) y)") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "x"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+}
+
+#[test]
+fn let_body_is_in_tail_position() {
+    let input = r#"
+    (define (count n) (let ((m (- n 1))) (if (= m 0) 0 (count m))))
+    (count 100000)
+    "#;
+
+    assert_eq!(run_integer(input), Integer::from(0));
+}
+
+#[test]
+fn begin_evaluates_in_order() {
+    let input = r#"
+    (define x 1)
+    (begin (define x (* x 10)) (define x (+ x 2)) x)
+    "#;
+
+    assert_eq!(run_integer(input), 12);
+
+    // `(begin)` cannot be parsed, but it can be built.
+    let empty = AST {
+        ast: ASTType::Begin(Vec::new()),
+        position: Position::new(1, 1),
+    };
+    let value = Interpreter::new().eval(empty).unwrap();
+    assert_eq!(value.to_string(), "()");
+}
+
+#[test]
+fn multi_expression_bodies() {
+    for (input, output) in &[
+        ("(define (f x) (define y (* x 2)) (+ y 1)) (f 3)", "7"),
+        ("((lambda (x) (define y x) (+ x y)) 4)", "8"),
+        ("(let ((x 1)) (define y 2) (+ x y))", "3"),
+        ("(begin 5)", "5"),
+    ] {
+        let value = run(&mut Interpreter::new(), input).unwrap();
+        assert_eq!(value.to_string(), *output, "input: {}", input);
+    }
+
+    // A `define` in a function body does not leak.
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, "(define (f) (define y 1) y) (f)").unwrap();
+    match run(&mut interpreter, "y") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "y"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+}
+
+#[test]
+fn begin_is_in_tail_position() {
+    let input = r#"
+    (define (count n) (if (= n 0) 0 (begin (- n 1) (count (- n 1)))))
+    (count 100000)
+    "#;
+
+    assert_eq!(run_integer(input), Integer::from(0));
+}
+
+#[test]
+fn cond_and_case() {
+    let mut interpreter = Interpreter::new();
+    run(
+        &mut interpreter,
+        r#"
+        (define (sign n)
+          (cond ((< n 0) "negative")
+                ((= n 0) "zero")
+                (else "positive")))
+        (define (name n)
+          (case n
+            ((1) "one")
+            ((2 3) (print n) "two or three")
+            ((-1) "minus one")
+            (else "many")))
+        "#,
+    )
+    .unwrap();
+
+    for (input, output) in &[
+        ("(sign -5)", "negative"),
+        ("(sign 0)", "zero"),
+        ("(sign 5)", "positive"),
+        ("(name 1)", "one"),
+        ("(name 3)", "two or three"),
+        ("(name -1)", "minus one"),
+        ("(name 4)", "many"),
+        ("(name \"1\")", "many"),
+        ("(cond (#f 1))", "0"),
+        ("(cond)", "0"),
+        ("(case 5 ((1) 1))", "0"),
+        ("(cond (#f undefined) (#t 1) (undefined 2))", "1"),
+    ] {
+        let value = run(&mut interpreter, input).unwrap();
+        assert_eq!(value.to_string(), *output, "input: {}", input);
+    }
+}
+
+#[test]
+fn cond_is_in_tail_position() {
+    let input = r#"
+    (define (count n) (cond ((= n 0) 0) (else (count (- n 1)))))
+    (define (count2 n) (case n ((0) 0) (else (count2 (- n 1)))))
+    (+ (count 100000) (count2 100000))
+    "#;
+
+    assert_eq!(run_integer(input), Integer::from(0));
+}
+
+#[test]
+fn set_assigns_nearest_binding() {
+    let input = r#"
+    (define (make-counter)
+      (let ((n 0))
+        (lambda () (set! n (+ n 1)) n)))
+    (define a (make-counter))
+    (define b (make-counter))
+    (a) (a) (b)
+    (define n 100)
+    (+ (* (a) 10) (b))
+    "#;
+
+    assert_eq!(run_integer(input), Integer::from(32));
+
+    let mut interpreter = Interpreter::new();
+    assert_eq!(
+        run(
+            &mut interpreter,
+            "(define x 1) (define (f x) (set! x 5) x) (+ (f 0) x)"
+        )
+        .unwrap()
+        .to_string(),
+        "6"
+    );
+    match run(&mut interpreter, "(set! y 1)") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "y"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+    // `set!` does not create a binding when the variable is unbound.
+    match run(&mut interpreter, "y") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "y"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+}
+
+/// The definitions of the macros `unless` and `while`.
+const MACROS: &str = r#"
+(defmacro (unless condition then otherwise)
+  (list (string->symbol "if") condition otherwise then))
+
+(defmacro (while condition body)
+  (let ((loop (gensym))
+        (symbol string->symbol))
+    (list (symbol "letrec")
+          (list (list loop (list (symbol "lambda") nil
+                                 (list (symbol "if") condition
+                                       (list (symbol "begin") body (list loop))
+                                       0))))
+          (list loop))))
+"#;
+
+#[test]
+fn macros() {
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, MACROS).unwrap();
+
+    assert_eq!(
+        run(&mut interpreter, r#"(unless (= 1 2) "yes" "no")"#)
+            .unwrap()
+            .to_string(),
+        "yes"
+    );
+    // The arguments of a macro are code, which is not evaluated unless the macro uses it.
+    assert_eq!(
+        run(&mut interpreter, "(unless #t (undefined) 2)")
+            .unwrap()
+            .to_string(),
+        "2"
+    );
+
+    let input = r#"
+    (define (sum-below n)
+      (let ((i 0) (total 0))
+        (while (< i n)
+          (begin (set! total (+ total i)) (set! i (+ i 1))))
+        total))
+    (sum-below 100)
+    "#;
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "4950");
+
+    // A macro can expand to a call to another macro.
+    let input = r#"
+    (defmacro (when condition then) (list (string->symbol "unless") condition 0 then))
+    (list (when #t 1) (when #f 1))
+    "#;
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "(1 0)");
+}
+
+#[test]
+fn local_variables_shadow_macros() {
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, MACROS).unwrap();
+
+    for (input, output) in &[
+        ("(define (f unless) (unless 1 2 3)) (f list)", "(1 2 3)"),
+        ("((lambda (unless) (unless 1 2 3)) list)", "(1 2 3)"),
+        ("(let ((unless list)) (unless 1 2 3))", "(1 2 3)"),
+        ("(let* ((f list) (unless f)) (unless 1 2 3))", "(1 2 3)"),
+        ("(letrec ((unless (lambda (x) x))) (unless 1))", "1"),
+        ("(define (g) (define (unless x) x) (unless 1)) (g)", "1"),
+        ("(try (error \"e\") (catch unless (car unless)))", "e"),
+        // Outside the scope of the variable, the macro is expanded.
+        ("(let ((unless list)) (unless 1 2 3)) (unless #f 1 2)", "1"),
+        ("(let ((x (unless #f 1 2)) (unless list)) x)", "1"),
+    ] {
+        let value = run(&mut interpreter, input).unwrap();
+        assert_eq!(value.to_string(), *output, "input: {}", input);
+    }
+}
+
+#[test]
+fn macro_errors_have_position_of_call() {
+    let mut interpreter = Interpreter::new();
+    run(&mut interpreter, MACROS).unwrap();
+
+    let input = "(defmacro (bad) (list (string->symbol \"if\")))\n  (bad)";
+    match run(&mut interpreter, input) {
+        Err(InterpreterError::MacroError {
+            name,
+            position,
+            error,
+        }) => {
+            assert_eq!(name, "bad");
+            assert_eq!(position, Position::new(2, 4));
+            assert!(matches!(*error, InterpreterError::InvalidCode { .. }));
+        }
+        v => panic!("expected macro error, found {:?}", v),
+    }
+
+    match run(&mut interpreter, "(defmacro (first x) (car x))\n(first 1)") {
+        Err(InterpreterError::MacroError {
+            name,
+            position,
+            error,
+        }) => {
+            assert_eq!(name, "first");
+            assert_eq!(position, Position::new(2, 2));
+            assert!(matches!(
+                *error,
+                InterpreterError::BuiltinTypeError { name: "car", .. }
+            ));
+        }
+        v => panic!("expected macro error, found {:?}", v),
+    }
+
+    // Code that comes from the arguments of a macro keeps its position, and other code that comes
+    // from a macro has the position of the call.
+    match run(&mut interpreter, "\n\n   (unless #f x 0)") {
+        Err(InterpreterError::UnknownVariable { name, position }) => {
+            assert_eq!(name, "x");
+            assert_eq!(position, Position::new(3, 15));
+        }
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+    match run(&mut interpreter, "(unless #f (car\n  (cons (f 1) 2)) 0)") {
+        Err(InterpreterError::UnknownVariable { name, position }) => {
+            assert_eq!(name, "f");
+            assert_eq!(position, Position::new(2, 10));
+        }
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+    let input = "(defmacro (oops) (list (string->symbol \"y\")))\n\n (oops)";
+    match run(&mut interpreter, input) {
+        Err(InterpreterError::UnknownVariable { name, position }) => {
+            assert_eq!(name, "y");
+            assert_eq!(position, Position::new(3, 3));
+        }
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+
+    interpreter.set_max_depth(50);
+    let input = "(defmacro (forever) (list (string->symbol \"forever\")))\n(forever)";
+    match run(&mut interpreter, input) {
+        Err(InterpreterError::MacroError { error, .. }) => {
+            assert!(matches!(*error, InterpreterError::StackOverflow { .. }))
+        }
+        v => panic!("expected macro error, found {:?}", v),
+    }
+}
+
+#[test]
+fn quote_and_unquote() {
+    let input = r#"
+    (define (f x) (print x) (set! x 1) x)
+    (lambda (x y) (let* ((z x)) (not (and x (or y z)))))
+    (cond ((= x 1) "one") (else other))
+    (case (f 1) ((1 2) 1.5) (else #f))
+    (letrec ((g (lambda () (g)))) (begin (g)))
+    (defmacro (m x) x)
+    '(a 'b `c ,d (quote e))
+    `(a ,b (c ,(d e)) `(f ,(g ,h)))
+    (try (f) (catch e (print e) e))
+    (import "lib.tini")
+    (import lib (only f g))
+    "#;
+
+    for expr in Parser::new(Lexer::new(input)) {
+        let expr = expr.unwrap();
+        let code = quote(&expr);
+        let unquoted = unquote(&code, expr.position).unwrap();
+        assert_eq!(quote(&unquoted).to_string(), code.to_string());
+    }
+
+    assert_eq!(
+        quote(
+            &Parser::new(Lexer::new("(if (< x 0) \"negative\" x)"))
+                .parse_expression()
+                .unwrap()
+        )
+        .to_string(),
+        "(if (< x 0) negative x)"
+    );
+
+    let position = Position::new(1, 1);
+    for code in &[
+        Value::list(vec![Rc::new(Value::Symbol("if".to_string()))]),
+        Value::Pair(
+            Rc::new(Value::Integer(1.into())),
+            Rc::new(Value::Integer(2.into())),
+        )
+        .into(),
+        Rc::new(Value::Box(RefCell::new(Rc::new(Value::Nil)))),
+    ] {
+        assert!(unquote(code, position).is_err(), "code: {}", code);
+    }
+}
+
+#[test]
+fn quote_and_quasiquote() {
+    let mut interpreter = Interpreter::new();
+    for (input, output) in &[
+        ("'x", "x"),
+        (
+            "'(1 \"two\" 3.0 #t (if else) ())",
+            "(1 two 3.0 #t (if else) ())",
+        ),
+        ("(quote (a 'b))", "(a (quote b))"),
+        ("(symbol? 'x)", "#t"),
+        ("(symbol->string 'lambda)", "lambda"),
+        ("(define x 5) `(x ,x ,(+ x 1))", "(x 5 6)"),
+        ("(quasiquote (x (unquote (* x 2))))", "(x 10)"),
+        ("`(a `(b ,(c ,x)))", "(a (quasiquote (b (unquote (c 5)))))"),
+        ("'(a ,x)", "(a (unquote x))"),
+        ("(eval '(+ 1 2))", "3"),
+        ("(eval (list '* x 2))", "10"),
+        ("(eval 7)", "7"),
+        ("(let ((x 1)) (eval 'x))", "5"),
+    ] {
+        assert_eq!(
+            run(&mut interpreter, input).unwrap().to_string(),
+            *output,
+            "input: {}",
+            input
+        );
+    }
+
+    // `eval` expands macros, and code from `eval` has the position of the call.
+    let input = "(defmacro (twice x) `(begin ,x ,x))\n(eval '(twice (set! x (+ x 1))))\nx";
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "7");
+    match run(&mut interpreter, "\n (eval '(+ y 1))") {
+        Err(InterpreterError::UnknownVariable { name, position }) => {
+            assert_eq!(name, "y");
+            assert_eq!(position, Position::new(2, 3));
+        }
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+    match run(&mut interpreter, "(eval (cons 1 2))") {
+        Err(InterpreterError::TypeError { expected, .. }) => assert_eq!(expected, "list as code"),
+        v => panic!("expected type error, found {:?}", v),
+    }
+}
+
+#[test]
+fn macros_with_quasiquote() {
+    let input = r#"
+    (defmacro (swap! a b)
+      (let ((tmp (gensym)))
+        `(let ((,tmp ,a))
+           (set! ,a ,b)
+           (set! ,b ,tmp))))
+    (define tmp 1)
+    (define y 2)
+    (swap! tmp y)
+    (list tmp y)
+    "#;
+
+    assert_eq!(
+        run(&mut Interpreter::new(), input).unwrap().to_string(),
+        "(2 1)"
+    );
+}
+
+#[test]
+fn error_and_try() {
+    let mut interpreter = Interpreter::new();
+
+    match run(&mut interpreter, "(define x 1)\n  (error \"bad value:\" x)") {
+        Err(error @ InterpreterError::UserError { .. }) => {
+            assert_eq!(error.to_string(), "error at 2:4: bad value: 1");
+            match error {
+                InterpreterError::UserError { message, value, .. } => {
+                    assert_eq!(message, "bad value:");
+                    assert_eq!(value.to_string(), "1");
+                }
+                _ => unreachable!(),
+            }
+        }
+        v => panic!("expected user error, found {:?}", v),
+    }
+
+    for (input, output) in &[
+        ("(try (+ 1 2) (catch e 0))", "3"),
+        (r#"(try (error "oops" '(1 2)) (catch e e))"#, "(oops (1 2))"),
+        (r#"(try (error "oops") (catch e e))"#, "(oops ())"),
+        // Runtime errors are caught, and described.
+        ("(try y (catch e (car e)))", "unknown variable y at 1:6"),
+        (
+            "(try (+ 1 \"a\") (catch e (car e)))",
+            "built-in function + expected argument of type number, but got string",
+        ),
+        ("(try (/ 1 0) (catch e (car (cdr e))))", "()"),
+        // Errors in functions that are called are caught, and the variable is only bound in the
+        // handler.
+        (
+            r#"(define (check n) (if (< n 0) (error "negative" n) n))
+               (define (safe n) (try (check n) (catch e (car (cdr e)))))
+               (list (safe 5) (safe -5))"#,
+            "(5 -5)",
+        ),
+        (
+            "(try (error \"a\") (catch e (try (error \"b\") (catch e e))))",
+            "(b ())",
+        ),
+        (
+            "(try (try (error \"a\") (catch e (error \"b\"))) (catch e e))",
+            "(b ())",
+        ),
+    ] {
+        assert_eq!(
+            run(&mut interpreter, input).unwrap().to_string(),
+            *output,
+            "input: {}",
+            input
+        );
+    }
+    match run(&mut interpreter, "e") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "e"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+
+    // A stack overflow is caught too, and the interpreter can still be used after it.
+    interpreter.set_max_depth(100);
+    let input = r#"
+    (define (sum n) (if (= n 0) 0 (+ n (sum (- n 1)))))
+    (try (sum 1000) (catch e "too deep"))
+    "#;
+    assert_eq!(
+        run(&mut interpreter, input).unwrap().to_string(),
+        "too deep"
+    );
+    assert_eq!(run(&mut interpreter, "(sum 10)").unwrap().to_string(), "55");
+}
+
+#[test]
+fn catch_handler_is_in_tail_position() {
+    let input = r#"
+    (define (count n) (try (error "again") (catch e (if (= n 0) 0 (count (- n 1))))))
+    (count 100000)
+    "#;
+
+    assert_eq!(run_integer(input), Integer::from(0));
+}
+
+/// Create a new directory called `name` for a test, containing `files`, which are pairs of a
+/// path and contents.
+fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&directory);
+    for (path, contents) in files {
+        let path = directory.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    directory
+}
+
+/// Returns the description of an `InterpreterError::ImportError` for the file `file`.
+fn import_error(result: Result<Rc<Value>, InterpreterError>, file: &str) -> InterpreterError {
+    match result {
+        Err(InterpreterError::ImportError { path, error, .. }) => {
+            assert!(
+                path.ends_with(file),
+                "error in {}, not in {}",
+                path.display(),
+                file
+            );
+            *error
+        }
+        v => panic!("expected import error, found {:?}", v),
+    }
+}
+
+#[test]
+fn import() {
+    let directory = directory(
+        "import",
+        &[
+            (
+                "lib.tini",
+                "(define (double x) (* x 2)) (define base 10) (define loads (box 0))",
+            ),
+            ("nested/outer.tini", "(import inner) (define x inner/x)"),
+            ("nested/inner.tini", "(define x 42)"),
+            (
+                "macros.tini",
+                r#"
+                (define (sum x y) (list (quote +) x y))
+                (defmacro (twice x) (sum x x))
+                (define four (twice 2))
+                (define (f) (one))
+                "#,
+            ),
+            (
+                "secret.tini",
+                r#"
+                (define secret 42)
+                (defmacro (hidden) (quote secret))
+                (define (get) (eval (quote secret)))
+                (define (expand) (eval (quote (hidden))))
+                (define copy (eval (quote (hidden))))
+                "#,
+            ),
+        ],
+    );
+    let mut interpreter = Interpreter::new();
+    interpreter.set_search_path(vec![directory.clone()]);
+
+    let input = r#"
+    (import "lib.tini")
+    (set-box! lib/loads (+ (unbox lib/loads) 1))
+    (import lib)
+    (list (lib/double lib/base) (unbox lib/loads))
+    "#;
+    // The file is only evaluated once, so the box is not replaced by the second import.
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "(20 1)");
+
+    assert_eq!(
+        run(&mut interpreter, "(import lib (only double)) (double 4)")
+            .unwrap()
+            .to_string(),
+        "8"
+    );
+    match run(&mut interpreter, "base") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "base"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+
+    // An imported file imports relative to its own directory.
+    let input = "(define (f) (import \"nested/outer.tini\") outer/x) (f)";
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "42");
+    // The import in `f` only defined `outer/x` in `f`.
+    assert!(run(&mut interpreter, "outer/x").is_err());
+
+    // The variables that a file imported are not imported from it.
+    let input = "(import \"nested/outer.tini\") outer/inner/x";
+    match run(&mut interpreter, input) {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "outer/inner/x"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+    let input = "(import \"nested/outer.tini\" (only inner/x))";
+    assert!(run(&mut interpreter, input).is_err());
+
+    // Macros are only used in the file that defines them, and they run in that file, which
+    // defines `sum`.
+    let input = "(defmacro (one) 1) (import macros) (list macros/four (one))";
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "(4 1)");
+    for input in &["(twice 1)", "(macros/f)"] {
+        match run(&mut interpreter, input) {
+            Err(InterpreterError::UnknownVariable { name, .. }) => {
+                assert!(name == "twice" || name == "one", "{}", name)
+            }
+            v => panic!("expected unknown variable error, found {:?}", v),
+        }
+    }
+
+    // `eval` evaluates code in the file that calls it, with the macros of that file.
+    let input = "(import secret) (list (secret/get) (secret/expand) secret/copy)";
+    assert_eq!(
+        run(&mut interpreter, input).unwrap().to_string(),
+        "(42 42 42)"
+    );
+    match run(&mut interpreter, "(eval 'secret)") {
+        Err(InterpreterError::UnknownVariable { name, .. }) => assert_eq!(name, "secret"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+}
+
+#[test]
+fn import_errors() {
+    let directory = directory(
+        "import_errors",
+        &[
+            ("a.tini", "(import b)"),
+            ("b.tini", "(import c)"),
+            ("c.tini", "(import a)"),
+            ("invalid.tini", "(define x 1)\n(define)"),
+            ("unknown.tini", "(define x y)"),
+            ("lib.tini", "(define x 1)"),
+        ],
+    );
+    let mut interpreter = Interpreter::new();
+    interpreter.set_search_path(vec![directory.clone()]);
+
+    // The cycle is found when `c.tini` imports `a.tini` again.
+    let mut error = import_error(run(&mut interpreter, "(import a)"), "a.tini");
+    for file in &["b.tini", "c.tini", "a.tini"] {
+        error = import_error(Err(error), file);
+    }
+    match error {
+        InterpreterError::ImportCycle { files } => {
+            let files = files
+                .iter()
+                .map(|file| file.file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(files, ["a.tini", "b.tini", "c.tini", "a.tini"]);
+        }
+        v => panic!("expected import cycle, found {:?}", v),
+    }
+
+    match import_error(run(&mut interpreter, "(import invalid)"), "invalid.tini") {
+        InterpreterError::SyntaxError(_) => {}
+        v => panic!("expected syntax error, found {:?}", v),
+    }
+
+    match import_error(run(&mut interpreter, "(import unknown)"), "unknown.tini") {
+        InterpreterError::UnknownVariable { name, position } => {
+            assert_eq!(name, "y");
+            assert_eq!(position, Position::new(1, 11));
+        }
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+
+    match import_error(run(&mut interpreter, "(import lib (only y))"), "lib.tini") {
+        InterpreterError::UnknownVariable { name, .. } => assert_eq!(name, "y"),
+        v => panic!("expected unknown variable error, found {:?}", v),
+    }
+
+    match run(&mut interpreter, "\n (import missing)") {
+        Err(error @ InterpreterError::ImportError { .. }) => assert_eq!(
+            error.to_string(),
+            "error while importing missing.tini at 2:3: file not found in the search path"
+        ),
+        v => panic!("expected import error, found {:?}", v),
+    }
+
+    // Import errors can be caught.
+    let input = "(try (import unknown) (catch e 0)) (import lib) lib/x";
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "1");
+
+    // A file that failed to import is not kept, so it can be imported after it is fixed.
+    fs::write(directory.join("unknown.tini"), "(define x 2)").unwrap();
+    let input = "(import unknown) unknown/x";
+    assert_eq!(run(&mut interpreter, input).unwrap().to_string(), "2");
+}
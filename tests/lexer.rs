@@ -18,18 +18,18 @@ fn lexer_test() {
         Identifier("=".to_string()),
         LeftBracket,
         Identifier("+".to_string()),
-        Integer(1),
-        Integer(2),
+        Integer(1.into()),
+        Integer(2.into()),
         RightBracket,
-        Integer(3),
+        Integer(3.into()),
         RightBracket,
         LeftBracket,
         Identifier("print".to_string()),
-        Integer(1),
+        Integer(1.into()),
         RightBracket,
         LeftBracket,
         Identifier("print".to_string()),
-        Integer(0),
+        Integer(0.into()),
         RightBracket,
         RightBracket,
     ];
@@ -41,3 +41,180 @@ fn lexer_test() {
         tokens
     )
 }
+
+#[test]
+#[cfg(not(feature = "bigint"))]
+fn integer_literal_overflow() {
+    let mut lexer = Lexer::new("9223372036854775807 -9223372036854775808 9223372036854775808");
+
+    assert_eq!(
+        lexer.next_token().unwrap().token,
+        TokenType::Integer(i64::MAX)
+    );
+    assert_eq!(
+        lexer.next_token().unwrap().token,
+        TokenType::Integer(i64::MIN)
+    );
+    match lexer.next_token() {
+        LexerResult::Err(LexerError::IntegerOverflow { literal, .. }) => {
+            assert_eq!(literal, "9223372036854775808")
+        }
+        t => panic!("expected integer overflow error, found {:?}", t),
+    }
+}
+
+#[test]
+fn string_literals() {
+    let input = r#""plain" "a\nb\tc" "\\ \"quoted\"" "\u{48}\u{1F600}" """#;
+
+    assert_eq!(
+        Lexer::new(input)
+            .map(|t| t.unwrap().token)
+            .collect::<Vec<TokenType>>(),
+        vec![
+            TokenType::String("plain".to_string()),
+            TokenType::String("a\nb\tc".to_string()),
+            TokenType::String("\\ \"quoted\"".to_string()),
+            TokenType::String("H\u{1F600}".to_string()),
+            TokenType::String(String::new()),
+        ]
+    )
+}
+
+#[test]
+fn invalid_string_literals() {
+    for (input, ch) in &[
+        (r#""\q""#, 'q'),
+        (r#""\u{110000}""#, 'u'),
+        (r#""\u41""#, 'u'),
+    ] {
+        match Lexer::new(input).next_token() {
+            LexerResult::Err(LexerError::UnknownEscape { ch: c, .. }) => assert_eq!(c, *ch),
+            t => panic!("expected unknown escape error, found {:?}", t),
+        }
+    }
+
+    match Lexer::new(r#""unterminated"#).next_token() {
+        LexerResult::Err(LexerError::UnexpectedEof { .. }) => {}
+        t => panic!("expected unexpected end of file error, found {:?}", t),
+    }
+}
+
+#[test]
+fn float_literals() {
+    let input = "1.5 1e10 2.5E-3 0.25e+2 1_000.5";
+
+    assert_eq!(
+        Lexer::new(input)
+            .map(|t| t.unwrap().token)
+            .collect::<Vec<TokenType>>(),
+        vec![
+            TokenType::Float(1.5),
+            TokenType::Float(1e10),
+            TokenType::Float(2.5e-3),
+            TokenType::Float(25.0),
+            TokenType::Float(1000.5),
+        ]
+    )
+}
+
+#[test]
+fn signed_literals() {
+    let input = "-5 +5 - -foo (- 5) -1.5 +-1";
+
+    assert_eq!(
+        Lexer::new(input)
+            .map(|t| t.unwrap().token)
+            .collect::<Vec<TokenType>>(),
+        vec![
+            TokenType::Integer((-5).into()),
+            TokenType::Integer(5.into()),
+            TokenType::Identifier("-".to_string()),
+            TokenType::Identifier("-foo".to_string()),
+            TokenType::LeftBracket,
+            TokenType::Identifier("-".to_string()),
+            TokenType::Integer(5.into()),
+            TokenType::RightBracket,
+            TokenType::Float(-1.5),
+            TokenType::Identifier("+-1".to_string()),
+        ]
+    )
+}
+
+#[test]
+fn radix_literals() {
+    let input = "0x1F 0XfF -0x10 0b1010 0o17 1_000_000 0xFF_FF 0b1111_0000";
+
+    assert_eq!(
+        Lexer::new(input)
+            .map(|t| t.unwrap().token)
+            .collect::<Vec<TokenType>>(),
+        vec![
+            TokenType::Integer(31.into()),
+            TokenType::Integer(255.into()),
+            TokenType::Integer((-16).into()),
+            TokenType::Integer(10.into()),
+            TokenType::Integer(15.into()),
+            TokenType::Integer(1_000_000.into()),
+            TokenType::Integer(65535.into()),
+            TokenType::Integer(240.into()),
+        ]
+    )
+}
+
+#[test]
+fn invalid_number_literals() {
+    for input in &[
+        "0x", "0b", "12ab", "0b102", "0o8", "0xG", "1_", "1__0", "0x_1", "1.", "1e", "1.2.3", "1-2",
+    ] {
+        match Lexer::new(input).next_token() {
+            LexerResult::Err(LexerError::InvalidNumber { literal, .. }) => {
+                assert_eq!(literal, *input)
+            }
+            t => panic!("expected invalid number error for {}, found {:?}", input, t),
+        }
+    }
+}
+
+#[test]
+fn boolean_literals_and_keywords() {
+    let input = "#t #f #true and or not";
+
+    assert_eq!(
+        Lexer::new(input)
+            .map(|t| t.unwrap().token)
+            .collect::<Vec<TokenType>>(),
+        vec![
+            TokenType::Bool(true),
+            TokenType::Bool(false),
+            TokenType::Identifier("#true".to_string()),
+            TokenType::And,
+            TokenType::Or,
+            TokenType::Not,
+        ]
+    )
+}
+
+#[test]
+fn quote_characters() {
+    let input = "'x `(a ,b) it's";
+
+    assert_eq!(
+        Lexer::new(input)
+            .map(|t| t.unwrap().token)
+            .collect::<Vec<TokenType>>(),
+        vec![
+            TokenType::QuoteMark,
+            TokenType::Identifier("x".to_string()),
+            TokenType::Backquote,
+            TokenType::LeftBracket,
+            TokenType::Identifier("a".to_string()),
+            TokenType::Comma,
+            TokenType::Identifier("b".to_string()),
+            TokenType::RightBracket,
+            TokenType::Identifier("it".to_string()),
+            TokenType::QuoteMark,
+            TokenType::Identifier("s".to_string()),
+        ]
+    )
+}
@@ -0,0 +1,289 @@
+use tini::parser::ParseError;
+use tini::prelude::*;
+
+/// Parse the first expression in `input`.
+fn parse(input: &str) -> ParseResult<AST> {
+    Parser::new(Lexer::new(input)).parse_expression()
+}
+
+#[test]
+fn let_forms() {
+    assert!(matches!(
+        parse("(let ((x 1)) x)").unwrap().ast,
+        ASTType::Let { .. }
+    ));
+    assert!(matches!(
+        parse("(let* ((x 1)) x)").unwrap().ast,
+        ASTType::LetStar { .. }
+    ));
+    assert!(matches!(
+        parse("(letrec () x)").unwrap().ast,
+        ASTType::Letrec { .. }
+    ));
+
+    match parse("(let ((x 1) (y (f))) x)").unwrap().ast {
+        ASTType::Let { bindings, .. } => {
+            let names = bindings
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, vec!["x", "y"]);
+            assert_eq!(bindings[0].1.ast, ASTType::Integer(1.into()));
+        }
+        ast => panic!("expected let expression, found {:?}", ast),
+    }
+}
+
+#[test]
+fn invalid_let_forms() {
+    for input in &[
+        "(let (x 1) x)",
+        "(let ((1 1)) x)",
+        "(let ((x 1 2)) x)",
+        "(let x x)",
+    ] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+    match parse("(let ((x 1))") {
+        ParseResult::Err(ParseError::UnexpectedEof { .. }) => {}
+        r => panic!("expected unexpected end of file error, found {:?}", r),
+    }
+}
+
+#[test]
+fn bodies() {
+    match parse("(define (f x) (print x) x)").unwrap().ast {
+        ASTType::Define { value, .. } => match value.ast {
+            ASTType::Begin(expressions) => assert_eq!(expressions.len(), 2),
+            ast => panic!("expected begin expression, found {:?}", ast),
+        },
+        ast => panic!("expected define expression, found {:?}", ast),
+    }
+    match parse("(lambda (x) x)").unwrap().ast {
+        ASTType::Lambda { value, .. } => assert_eq!(value.ast, ASTType::Identifier("x".into())),
+        ast => panic!("expected lambda expression, found {:?}", ast),
+    }
+
+    for input in &[
+        "(begin)",
+        "(lambda (x))",
+        "(define (f x))",
+        "(define x 1 2)",
+    ] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}
+
+#[test]
+fn malformed_clauses() {
+    for (input, line, column) in &[
+        ("(cond x)", 1, 7),
+        ("(cond ())", 1, 7),
+        ("(cond (#t 1)\n      (#f))", 2, 7),
+        ("(cond (else 1) (#t 2))", 1, 16),
+        ("(cond (else))", 1, 7),
+        ("(case 1 (1 2))", 1, 9),
+        ("(case 1 ((1) 2)\n  ((x) 3))", 2, 3),
+        ("(case 1 ((1.5) 2))", 1, 9),
+    ] {
+        match parse(input) {
+            ParseResult::Err(ParseError::MalformedClause { position, .. }) => {
+                assert_eq!(position, Position::new(*line, *column), "input: {}", input)
+            }
+            r => panic!(
+                "expected malformed clause error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}
+
+#[test]
+fn set() {
+    match parse("(set! x 1)").unwrap().ast {
+        ASTType::Set { name, value } => {
+            assert_eq!(name, "x");
+            assert_eq!(value.ast, ASTType::Integer(1.into()));
+        }
+        ast => panic!("expected set! expression, found {:?}", ast),
+    }
+    for input in &["(set! 1 2)", "(set! x 1 2)", "(set! (x) 1)"] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}
+
+#[test]
+fn defmacro() {
+    match parse("(defmacro (unless c x y) (list c y x))").unwrap().ast {
+        ASTType::Defmacro {
+            name, arguments, ..
+        } => {
+            assert_eq!(name, "unless");
+            assert_eq!(arguments, vec!["c", "x", "y"]);
+        }
+        ast => panic!("expected defmacro expression, found {:?}", ast),
+    }
+    for input in &["(defmacro m 1)", "(defmacro () 1)", "(defmacro (m 1) 1)"] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}
+
+#[test]
+fn from_tokens() {
+    let tokens = vec![
+        Token::new(TokenType::LeftBracket, 1, 1),
+        Token::new(TokenType::Identifier("f".to_string()), 1, 2),
+        Token::new(TokenType::Integer(1.into()), 1, 4),
+        Token::new(TokenType::RightBracket, 1, 5),
+    ];
+    let mut parser = Parser::from_tokens(tokens);
+    assert_eq!(
+        parser.parse_expression().unwrap(),
+        Parser::new(Lexer::new("(f 1)")).parse_expression().unwrap()
+    );
+    assert!(parser.parse_expression().is_eof());
+}
+
+#[test]
+fn quote_forms() {
+    use tini::ast::Datum;
+
+    let symbol = |name: &str| Datum::Symbol(name.to_string());
+
+    for input in &["'(if () 1)", "(quote (if () 1))"] {
+        assert_eq!(
+            parse(input).unwrap().ast,
+            ASTType::Quote(Datum::List(vec![
+                symbol("if"),
+                Datum::List(vec![]),
+                Datum::Integer(1.into()),
+            ])),
+            "input: {}",
+            input
+        );
+    }
+    // In a quote, `'x` and `,x` are read as lists.
+    assert_eq!(
+        parse("'('a ,b)").unwrap().ast,
+        ASTType::Quote(Datum::List(vec![
+            Datum::List(vec![symbol("quote"), symbol("a")]),
+            Datum::List(vec![symbol("unquote"), symbol("b")]),
+        ]))
+    );
+
+    for input in &["`(a ,(f x) ,b)", "(quasiquote (a (unquote (f x)) ,b))"] {
+        match parse(input).unwrap().ast {
+            ASTType::Quasiquote(Datum::List(elements)) => {
+                assert_eq!(elements[0], symbol("a"));
+                match (&elements[1], &elements[2]) {
+                    (Datum::Unquote(f), Datum::Unquote(b)) => {
+                        assert!(matches!(f.ast, ASTType::FunctionCall { .. }));
+                        assert_eq!(b.ast, parse("b").unwrap().ast);
+                    }
+                    d => panic!("expected unquoted expressions, found {:?}", d),
+                }
+            }
+            ast => panic!("expected quasiquote expression, found {:?}", ast),
+        }
+    }
+    for input in &[",x", "(unquote x)", "')", "(quote x y)"] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}
+
+#[test]
+fn try_forms() {
+    match parse("(try (f) (catch e (print e) 0))").unwrap().ast {
+        ASTType::Try { name, handler, .. } => {
+            assert_eq!(name, "e");
+            assert!(matches!(handler.ast, ASTType::Begin(_)));
+        }
+        ast => panic!("expected try expression, found {:?}", ast),
+    }
+    for input in &[
+        "(try (f))",
+        "(try (f) (e 0))",
+        "(try (f) (catch 1 0))",
+        "(try (f) (catch e 0) 1)",
+        "(catch e 0)",
+    ] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}
+
+#[test]
+fn import_forms() {
+    for (input, path, names) in &[
+        ("(import \"lib/util.tini\")", "lib/util.tini", None),
+        ("(import util)", "util.tini", None),
+        (
+            "(import util (only f g))",
+            "util.tini",
+            Some(vec!["f", "g"]),
+        ),
+    ] {
+        match parse(input).unwrap().ast {
+            ASTType::Import { path: p, names: n } => {
+                assert_eq!(p, *path);
+                assert_eq!(
+                    n,
+                    names
+                        .as_ref()
+                        .map(|names| names.iter().map(|n| n.to_string()).collect())
+                );
+            }
+            ast => panic!("expected import expression, found {:?}", ast),
+        }
+    }
+    for input in &[
+        "(import 1)",
+        "(import util f)",
+        "(import util (except f))",
+        "(import util (only 1))",
+        "(import util (only f) g)",
+    ] {
+        match parse(input) {
+            ParseResult::Err(ParseError::UnexpectedToken(_)) => {}
+            r => panic!(
+                "expected unexpected token error for {}, found {:?}",
+                input, r
+            ),
+        }
+    }
+}